@@ -0,0 +1,51 @@
+use crate::attack::single_byte_xor::*;
+use crate::data::*;
+
+// Two messages XORed with the same pad give c1 ^ c2 = m1 ^ m2, so guessing a crib in one message at some offset reveals
+// the other message at that offset. Returns the offsets where that reveals mostly printable text, each with the text,
+// ranked by chi-squared against English (best first). Ciphertexts of different lengths are compared over the shorter.
+#[allow(dead_code)]
+pub fn crib_drag(c1: &ASCIIData, c2: &ASCIIData, crib: &ASCIIData) -> Vec<(usize, ASCIIData)> {
+  let len = c1.len().min(c2.len());
+  if crib.is_empty() || crib.len() > len {
+    return vec![];
+  }
+
+  let pads = &c1.slice(0..len) ^ &c2.slice(0..len);
+  let mut candidates = Vec::from_iter(
+    (0..=len - crib.len())
+      .map(|offset| (offset, &pads.slice(offset..offset + crib.len()) ^ crib))
+      .filter(|(_, text)| looks_like_text(text))
+      .map(|(offset, text)| (offset, chi_squared_score(&text), text))
+  );
+
+  candidates.sort_by(|(_, s1, _), (_, s2, _)| s1.total_cmp(s2));
+
+  return Vec::from_iter(candidates.into_iter().map(|(offset, _, text)| (offset, text)));
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+  use crate::crypto::rc4::*;
+
+  #[test]
+  fn test_crib_drag() -> Result<(), String> {
+    let m1 = ASCIIData::from("Meet me by the old mill and we will attack at dawn if the weather holds up.");
+    let m2 = ASCIIData::from("Bring the ledger with you because the accountant wants to see it tomorrow.");
+    let crib = ASCIIData::from("attack at dawn");
+    let offset = 36;
+
+    let pad = Rc4::new(b"one time, honest").keystream(m1.len());
+    let (c1, c2) = (&m1 ^ &pad, &pad.slice(0..m2.len()) ^ &m2);
+
+    let ranked = crib_drag(&c1, &c2, &crib);
+    match ranked.first() {
+      Some((o, text)) if *o == offset && *text == m2.slice(offset..offset + crib.len()) => {},
+      Some((o, text)) => return Err(format!("offset {} ranked first with {:?}", o, text.to_string())),
+      None => return Err("no offset was printable".to_string())
+    }
+
+    return Ok(());
+  }
+}
@@ -0,0 +1,129 @@
+use crate::data::*;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+#[cfg(feature = "rand")]
+use crate::crypto::rc4::rc4;
+
+#[allow(dead_code)]
+const SESSION_ID: &str = "TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+
+// Candidate secret characters: the base64 alphabet and padding, plus the newline that ends the Cookie header.
+#[allow(dead_code)]
+const SECRET_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=\n";
+
+#[allow(dead_code)]
+pub fn format_request(body: &ASCIIData) -> ASCIIData {
+  let mut request = Vec::from(format!(
+    "POST / HTTP/1.1\nHost: hapless.com\nCookie: sessionid={}\nContent-Length: {}\n",
+    SESSION_ID,
+    body.len()
+  ));
+
+  request.extend(body.bytes());
+  return ASCIIData::from(request);
+}
+
+#[allow(dead_code)]
+pub fn compress(data: &ASCIIData) -> ASCIIData {
+  let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+  encoder.write_all(data.bytes()).unwrap();
+  return ASCIIData::from(encoder.finish().unwrap());
+}
+
+// Challenge 51, Set 7
+// Compresses the request carrying the session cookie, encrypts it with RC4 under a fresh key and leaks the length. A
+// stream cipher preserves length, so this is the compressed length itself.
+#[cfg(feature = "rand")]
+#[allow(dead_code)]
+pub fn compression_oracle(input: &ASCIIData) -> usize {
+  rc4(&compress(&format_request(input)), &ASCIIData::random(16)).len()
+}
+
+// Recovers the session id one character at a time by keeping whichever candidate compresses best alongside the cookie.
+// A single comparison is unreliable: deflate output is byte-granular (and block ciphers round it further up to whole
+// blocks), and when the extended match crosses into a new length code it can cost as much as the wrong candidate's
+// literal. So candidates are compared in a series of configurations, dropping leading bytes of the known text to move
+// the match length across length-code boundaries and prepending incompressible junk to move the output across byte
+// and block boundaries. Every configuration in which some candidates come out strictly shorter than others is a vote
+// for those candidates, and voting stops once one candidate leads by a clear margin.
+#[allow(dead_code)]
+pub fn crime_attack(oracle: impl Fn(&ASCIIData) -> usize) -> ASCIIData {
+  const MARGIN: usize = 3;
+
+  let mut known = Vec::from("sessionid=");
+  let mut secret = Vec::new();
+
+  loop {
+    let mut votes = vec![0; SECRET_ALPHABET.len()];
+
+    'configurations: for junk_len in 0..16 {
+      for drop in 0..10 {
+        let lengths = Vec::from_iter(SECRET_ALPHABET.iter().map(|&c| {
+          let mut guess = Vec::from_iter((0..junk_len).map(|i| 128 + i as u8));
+          guess.extend(&known[drop..]);
+          guess.push(c);
+          return oracle(&ASCIIData::from(guess));
+        }));
+
+        let shortest = *lengths.iter().min().unwrap();
+        if lengths.iter().all(|&len| len == shortest) {
+          continue;
+        }
+
+        for (i, &len) in lengths.iter().enumerate() {
+          if len == shortest {
+            votes[i] += 1;
+          }
+        }
+
+        let mut ranked = votes.clone();
+        ranked.sort_unstable_by(|a, b| b.cmp(a));
+        if ranked[0] >= ranked[1] + MARGIN {
+          break 'configurations;
+        }
+      }
+    }
+
+    let best = (0..SECRET_ALPHABET.len()).max_by_key(|&i| votes[i]).unwrap();
+    match SECRET_ALPHABET[best] {
+      b'\n' => return ASCIIData::from(secret),
+      c => {
+        known.push(c);
+        secret.push(c);
+      }
+    }
+  }
+}
+
+#[cfg(feature = "rand")]
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_crime_stream_cipher() -> Result<(), String> {
+    let res = crime_attack(compression_oracle);
+    if res.to_string() != SESSION_ID {
+      return Err(format!("recovered {:?}", res.to_string()));
+    }
+
+    return Ok(());
+  }
+
+  // There is no block cipher in the tree yet, so CBC is stood in for by what it leaks: the PKCS#7-padded length.
+  #[test]
+  fn test_crime_block_cipher() -> Result<(), String> {
+    let res = crime_attack(|input| {
+      let len = compression_oracle(input);
+      return len + 16 - len % 16;
+    });
+
+    if res.to_string() != SESSION_ID {
+      return Err(format!("recovered {:?}", res.to_string()));
+    }
+
+    return Ok(());
+  }
+}
@@ -0,0 +1,141 @@
+use crate::attack::oracle::*;
+use crate::data::*;
+
+// The longest run of input the discovery helpers feed an oracle before giving up on its output ever growing.
+const MAX_PROBE_LEN: usize = 256;
+
+// Feeds the oracle ever longer runs of a constant byte until its output grows. With block padding the output length
+// only changes in whole blocks, so the size of that first jump is the block size. None if the output has not grown
+// after MAX_PROBE_LEN bytes of input, as with a fixed-length or truncating oracle.
+#[allow(dead_code)]
+pub fn discover_block_size(oracle: &(impl Oracle + ?Sized)) -> Option<usize> {
+  let base = oracle.query(&ASCIIData::from("")).len();
+
+  let len = (1..=MAX_PROBE_LEN).map(|i| oracle.query(&ASCIIData::repeated(b'A', i)).len()).find(|&len| len > base)?;
+  return Some(len - base);
+}
+
+// Finds the length of the secret suffix a PKCS#7-padding oracle appends to our input (with no prefix in front of it).
+// Padding always adds 1 to block_size bytes, so the output first grows by a block at the smallest input length i for
+// which input and suffix exactly fill whole blocks, and at that point the unpadded output was base = i + suffix. None
+// if the output never grows within MAX_PROBE_LEN bytes, or grows before base - i makes sense, as it does at once for an
+// unpadded stream oracle with an empty suffix.
+#[allow(dead_code)]
+pub fn discover_suffix_len(oracle: &(impl Oracle + ?Sized)) -> Option<usize> {
+  let base = oracle.query(&ASCIIData::from("")).len();
+
+  let i = (1..=MAX_PROBE_LEN).find(|&i| oracle.query(&ASCIIData::repeated(b'A', i)).len() > base)?;
+  return base.checked_sub(i);
+}
+
+// The number of block_size chunks of data that duplicate an earlier chunk. ECB encrypts equal plaintext blocks to equal
+// ciphertext blocks, so any repeat at all is a strong sign of it; random-looking ciphertext of any length essentially
+// never repeats a 16-byte block by chance. A trailing partial block is ignored. None for a zero block size.
+#[allow(dead_code)]
+pub fn count_repeated_blocks(data: &ASCIIData, block_size: usize) -> Option<usize> {
+  if block_size == 0 {
+    return None;
+  }
+
+  let mut seen = std::collections::HashSet::new();
+  return Some(data.bytes().chunks_exact(block_size).filter(|block| !seen.insert(*block)).count());
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  // There is no AES in the tree yet; only the output length matters here, so the stand-in oracle just PKCS#7-pads
+  // input || suffix to the block size and scrambles it with a keystream.
+  #[allow(dead_code)]
+  fn padding_oracle(block_size: usize, suffix: &ASCIIData) -> impl Fn(&ASCIIData) -> ASCIIData + '_ {
+    move |input| {
+      let mut plaintext = input.bytes().clone();
+      plaintext.extend(suffix.bytes());
+
+      let padding = block_size - plaintext.len() % block_size;
+      plaintext.extend(vec![padding as u8; padding]);
+
+      return xor_cycled(&ASCIIData::from(plaintext), &ASCIIData::from("YELLOW SUBMARINE"));
+    }
+  }
+
+  #[test]
+  fn test_discover_block_size() -> Result<(), String> {
+    let suffix = ASCIIData::from("Rollin' in my 5.0");
+
+    for block_size in [8, 16, 32] {
+      let res = discover_block_size(&padding_oracle(block_size, &suffix));
+      if res != Some(block_size) {
+        return Err(format!("detected block size {:?} instead of {}", res, block_size));
+      }
+    }
+
+    // A fixed-length oracle never grows, so there is no jump to measure.
+    let res = discover_block_size(&|_: &ASCIIData| ASCIIData::repeated(0, 20));
+    if res.is_some() {
+      return Err(format!("detected block size {:?} for a fixed-length oracle", res));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_discover_suffix_len() -> Result<(), String> {
+    for len in [0, 1, 15, 16, 17, 138] {
+      let suffix = ASCIIData::repeated(b'x', len);
+      let res = discover_suffix_len(&padding_oracle(16, &suffix));
+      if res != Some(len) {
+        return Err(format!("detected suffix length {:?} instead of {}", res, len));
+      }
+    }
+
+    // An unpadded stream oracle with no suffix grows by a byte per input byte, which no padded suffix explains.
+    let res = discover_suffix_len(&|input: &ASCIIData| input.clone());
+    if res.is_some() {
+      return Err(format!("detected suffix length {:?} for a stream oracle", res));
+    }
+
+    return Ok(());
+  }
+
+  // The attacks only see the Oracle interface, so a boxed trait object works as well as a closure.
+  #[test]
+  fn test_trait_object_oracle() -> Result<(), String> {
+    let suffix = ASCIIData::from("Rollin' in my 5.0");
+    let oracle: Box<dyn Oracle + '_> = Box::new(padding_oracle(16, &suffix));
+
+    let (block_size, suffix_len) = (discover_block_size(oracle.as_ref()), discover_suffix_len(oracle.as_ref()));
+    if block_size != Some(16) || suffix_len != Some(suffix.len()) {
+      return Err(format!("detected block size {:?} and suffix length {:?}", block_size, suffix_len));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_count_repeated_blocks() -> Result<(), String> {
+    let block = ASCIIData::from("YELLOW SUBMARINE");
+    let mut data = &block + &ASCIIData::from("0123456789abcdef");
+    data.extend(&block);
+    data.extend(&block);
+    data.extend(&ASCIIData::from("YELLOW"));
+
+    let res = count_repeated_blocks(&data, 16);
+    if res != Some(2) {
+      return Err(format!("counted {:?} repeated blocks instead of 2", res));
+    }
+
+    let res = count_repeated_blocks(&data, 10);
+    if res != Some(0) {
+      return Err(format!("counted {:?} repeated 10-byte blocks instead of 0", res));
+    }
+
+    let res = count_repeated_blocks(&data, 0);
+    if res.is_some() {
+      return Err(format!("counted {:?} repeated empty blocks", res));
+    }
+
+    return Ok(());
+  }
+}
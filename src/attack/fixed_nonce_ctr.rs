@@ -0,0 +1,77 @@
+use crate::data::*;
+
+// Shown by reveal in place of bytes whose keystream byte has not been guessed yet.
+pub const UNKNOWN_BYTE: u8 = b'?';
+
+// Challenge 19, Set 3: every ciphertext was encrypted under the same CTR keystream, so fixing one keystream byte by
+// guessing a plaintext byte in one line decrypts that position in every other line too. The keystream covers the
+// longest ciphertext and starts out entirely unknown.
+#[allow(dead_code)]
+pub struct KeystreamSolver {
+  ciphertexts: Vec<ASCIIData>,
+  keystream: Vec<Option<u8>>
+}
+
+impl KeystreamSolver {
+  #[allow(dead_code)]
+  pub fn new(ciphertexts: Vec<ASCIIData>) -> KeystreamSolver {
+    let len = ciphertexts.iter().map(|c| c.len()).max().unwrap_or(0);
+    return KeystreamSolver { ciphertexts, keystream: vec![None; len] };
+  }
+
+  #[allow(dead_code)]
+  pub fn keystream(&self) -> &[Option<u8>] { &self.keystream }
+
+  // Sets keystream byte pos so that ciphertext line decrypts to value there. Panics if line is that short.
+  #[allow(dead_code)]
+  pub fn guess_byte(&mut self, line: usize, pos: usize, value: u8) {
+    let ciphertext = &self.ciphertexts[line];
+    if pos >= ciphertext.len() {
+      panic!("Cannot guess byte {} of line {}, which has length {}", pos, line, ciphertext.len());
+    }
+
+    self.keystream[pos] = Some(ciphertext[pos] ^ value);
+  }
+
+  // Decrypts every line with the keystream guessed so far, with UNKNOWN_BYTE wherever it is still missing.
+  #[allow(dead_code)]
+  pub fn reveal(&self) -> Vec<ASCIIData> {
+    let decrypt = |c: &ASCIIData| {
+      ASCIIData::from_iter(c.iter().zip(&self.keystream).map(|(&b, k)| k.map_or(UNKNOWN_BYTE, |k| b ^ k)))
+    };
+
+    return Vec::from_iter(self.ciphertexts.iter().map(decrypt));
+  }
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+  use crate::crypto::rc4::*;
+
+  #[test]
+  fn test_keystream_solver() -> Result<(), String> {
+    let plaintexts = ["I have met them at close of day", "Coming with vivid faces", "From counter or desk"];
+    let keystream = Rc4::new(b"fixed nonce").keystream(31);
+    let ciphertexts = Vec::from_iter(plaintexts.iter().map(|p| xor_cycled(&ASCIIData::from(*p), &keystream)));
+
+    let mut solver = KeystreamSolver::new(ciphertexts);
+    for (pos, &value) in b"I have".iter().enumerate() {
+      solver.guess_byte(0, pos, value);
+    }
+    solver.guess_byte(2, 19, b'k');
+
+    let expected = ["I have?????????????c???????????", "Coming?????????????a???", "From c?????????????k"];
+    for (i, line) in solver.reveal().iter().enumerate() {
+      if line.to_string() != expected[i] {
+        return Err(format!("line {} revealed as {}", i, line));
+      }
+    }
+
+    if solver.keystream()[19] != Some(keystream[19]) {
+      return Err(format!("wrong keystream byte {:?}", solver.keystream()[19]));
+    }
+
+    return Ok(());
+  }
+}
@@ -0,0 +1,171 @@
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Zero};
+
+use crate::data::*;
+use crate::hash::hmac::*;
+use crate::math::factor::*;
+use crate::math::modular::*;
+use crate::pubkey::ec::*;
+
+// The message the victim authenticates with a key derived from the shared point.
+pub const MAC_MESSAGE: &str = "crazy flamboyant for the rap enjoyment";
+
+// Subgroups are only used if their order is at most this, since each residue is found by trying every candidate.
+pub const SMALL_ORDER_BOUND: u64 = 1 << 16;
+
+// The victim's MAC under a shared point: HMAC-SHA256 of MAC_MESSAGE keyed with the point's coordinates (big-endian, x
+// then y).
+#[allow(dead_code)]
+pub fn mac_for_shared_point(shared: &EcPoint) -> ASCIIData {
+  let key = match shared {
+    EcPoint::Infinity => vec![],
+    EcPoint::Affine(x, y) => [x.to_bytes_be().1, y.to_bytes_be().1].concat()
+  };
+
+  return hmac_sha256(&ASCIIData::from(key), &ASCIIData::from(MAC_MESSAGE));
+}
+
+// A point of order exactly r on the curve, for a prime r dividing its number of points. Walks x = 0, 1, 2, ... for
+// points on the curve and multiplies each by the cofactor of the whole r-power part of the order (dividing out only a
+// single r is not enough when r^2 divides the order, e.g. if the 2-torsion is Z/2 x Z/2), which leaves a point of order
+// r^j. Unless that is the point at infinity, multiplying by r until the next step would vanish gives order exactly r.
+#[allow(dead_code)]
+pub fn small_order_point(curve: &Curve, r: &BigInt) -> EcPoint {
+  if !(&curve.order % r).is_zero() {
+    panic!("Cannot find a point of order {} on a curve with {} points", r, curve.order);
+  }
+
+  let mut cofactor = curve.order.clone();
+  while (&cofactor % r).is_zero() {
+    cofactor /= r;
+  }
+
+  let mut x = BigInt::zero();
+  loop {
+    let rhs = (&x * &x * &x + &curve.a * &x + &curve.b) % &curve.p;
+    if let Some(y) = sqrt_mod(&rhs, &curve.p) {
+      let mut point = curve.scalar_mul(&EcPoint::Affine(x.clone(), y), &cofactor);
+      if point != EcPoint::Infinity {
+        let mut next = curve.scalar_mul(&point, r);
+        while next != EcPoint::Infinity {
+          point = next;
+          next = curve.scalar_mul(&point, r);
+        }
+
+        return point;
+      }
+    }
+
+    x += 1;
+  }
+}
+
+// Challenge 59, Set 8: the victim multiplies whatever point it is sent by its private key without checking the point is
+// on its curve, and the group law never uses b. So a point of small prime order r on one of these curves (same p and a,
+// different b) pulls the key down to a residue mod r, which the MAC on the result gives away after at most r guesses.
+// Residues from distinct primes are combined with CRT until their product exceeds q, the order of the victim's base
+// point (SET8_Q for Set 8), which pins down the key. None if the curves' small subgroups run out before that.
+#[allow(dead_code)]
+pub fn invalid_curve_attack(oracle: impl Fn(&EcPoint) -> ASCIIData, curves: &[Curve], q: &BigInt) -> Option<BigInt> {
+  let mut residues: Vec<(BigInt, BigInt)> = vec![];
+  let mut modulus = BigInt::one();
+
+  for curve in curves {
+    let order = curve.order.to_biguint().expect("curve orders are positive");
+    let mut primes = factorize(&order, SMALL_ORDER_BOUND);
+    primes.dedup();
+
+    for r in primes.into_iter().filter(|r| *r <= BigUint::from(SMALL_ORDER_BOUND)).map(BigInt::from) {
+      if modulus > *q || residues.iter().any(|(_, m)| *m == r) {
+        continue;
+      }
+
+      let h = small_order_point(curve, &r);
+      let mac = oracle(&h);
+
+      // k * h for k = 0, 1, ..., r - 1, until its MAC matches.
+      let mut guess = EcPoint::Infinity;
+      let mut k = BigInt::zero();
+      while k < r && mac_for_shared_point(&guess) != mac {
+        guess = curve.add(&guess, &h);
+        k += 1;
+      }
+
+      if k < r {
+        modulus *= &r;
+        residues.push((k, r));
+      }
+    }
+  }
+
+  if modulus <= *q {
+    return None;
+  }
+
+  return Some(crt(&residues).expect("residues modulo distinct primes are always consistent"));
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  // The three weak curves from Challenge 59: the Set 8 curve's p and a with b = 210, 504 and 727.
+  #[allow(dead_code)]
+  fn weak_curves() -> Vec<Curve> {
+    let orders = [
+      "233970423115425145550826547352470124412",
+      "233970423115425145544350131142039591210",
+      "233970423115425145545378039958152057148"
+    ];
+
+    return Vec::from_iter([210, 504, 727].iter().zip(orders).map(|(&b, order)| Curve {
+      b: BigInt::from(b),
+      order: BigInt::parse_bytes(order.as_bytes(), 10).unwrap(),
+      ..Curve::set8()
+    }));
+  }
+
+  #[test]
+  fn test_small_order_point() -> Result<(), String> {
+    for curve in weak_curves() {
+      for r in [2, 7, 11, 61, 4999] {
+        let r = BigInt::from(r);
+        if !(&curve.order % &r).is_zero() {
+          continue;
+        }
+
+        let point = small_order_point(&curve, &r);
+        if !curve.contains(&point) || point == EcPoint::Infinity {
+          return Err(format!("point of order {} is not a finite point on the curve", r));
+        }
+
+        if curve.scalar_mul(&point, &r) != EcPoint::Infinity {
+          return Err(format!("point does not have order {}", r));
+        }
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_invalid_curve_attack() -> Result<(), String> {
+    let victim_curve = Curve::set8();
+    let private = BigInt::parse_bytes(b"18446744073709551629123456789012345", 10).unwrap() % BigInt::from(SET8_Q);
+    let oracle = |point: &EcPoint| mac_for_shared_point(&victim_curve.scalar_mul(point, &private));
+
+    let q = BigInt::from(SET8_Q);
+    let recovered = invalid_curve_attack(oracle, &weak_curves(), &q);
+    if recovered != Some(private.clone()) {
+      return Err(format!("recovered {:?} instead of {}", recovered, private));
+    }
+
+    // One weak curve's small subgroups multiply to less than q, which leaves the key ambiguous.
+    let recovered = invalid_curve_attack(oracle, &weak_curves()[..1], &q);
+    if recovered.is_some() {
+      return Err(format!("recovered {:?} from a single curve", recovered));
+    }
+
+    return Ok(());
+  }
+}
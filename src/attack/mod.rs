@@ -1,2 +1,11 @@
-mod repeating_key_xor;
-mod single_byte_xor;
+pub mod crib_drag;
+pub mod crime;
+pub mod ecb;
+pub mod fixed_nonce_ctr;
+pub mod invalid_curve;
+pub mod oracle;
+pub mod rc4_bias;
+pub mod repeating_key_xor;
+pub mod rsa_broadcast;
+pub mod single_byte_xor;
+pub mod suggest;
@@ -0,0 +1,89 @@
+use crate::data::*;
+
+// Something an attack can send chosen input to and get the resulting ciphertext (or whatever else it leaks) back from.
+// Any Fn(&ASCIIData) -> ASCIIData closure is an oracle, so a local simulation, a struct holding a secret key or a
+// network-backed client can all be handed to the same attack, including as a &dyn Oracle.
+pub trait Oracle {
+  fn query(&self, input: &ASCIIData) -> ASCIIData;
+}
+
+impl<F: Fn(&ASCIIData) -> ASCIIData> Oracle for F {
+  fn query(&self, input: &ASCIIData) -> ASCIIData { self(input) }
+}
+
+// Challenge 14, Set 2
+// Wraps an oracle so that every query is preceded by the same fixed prefix, which attacks have to find the length of
+// before they can line up their own input with block boundaries.
+pub struct PrefixOracle<O: Oracle> {
+  prefix: ASCIIData,
+  inner: O
+}
+
+impl<O: Oracle> PrefixOracle<O> {
+  #[allow(dead_code)]
+  pub fn with_prefix(inner: O, prefix: ASCIIData) -> PrefixOracle<O> { PrefixOracle { prefix, inner } }
+
+  // Picks a prefix of 0 to 63 random bytes from rng. Pass rand::rng() normally, or a seeded generator to get the same
+  // prefix every run.
+  #[cfg(feature = "rand")]
+  #[allow(dead_code)]
+  pub fn new(inner: O, rng: &mut impl rand::RngCore) -> PrefixOracle<O> {
+    PrefixOracle::with_prefix(inner, ASCIIData::random_in_with(rng, 0..64))
+  }
+
+  #[allow(dead_code)]
+  pub fn prefix(&self) -> &ASCIIData { &self.prefix }
+}
+
+impl<O: Oracle> Oracle for PrefixOracle<O> {
+  fn query(&self, input: &ASCIIData) -> ASCIIData { self.inner.query(&(&self.prefix + input)) }
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_prefix_oracle() -> Result<(), String> {
+    let inner = |input: &ASCIIData| input.xor_with_byte(0x20);
+    let oracle = PrefixOracle::with_prefix(inner, ASCIIData::from("PREFIX"));
+
+    let res = oracle.query(&ASCIIData::from("input"));
+    if res != ASCIIData::from("prefixINPUT") {
+      return Err(format!("wrong output: {}", res));
+    }
+
+    return Ok(());
+  }
+
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_seeded_prefix_oracle() -> Result<(), String> {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let inner = |input: &ASCIIData| input.clone();
+    let first = PrefixOracle::new(inner, &mut StdRng::seed_from_u64(1337));
+    let second = PrefixOracle::new(inner, &mut StdRng::seed_from_u64(1337));
+
+    if first.prefix() != second.prefix() {
+      return Err(format!(
+        "prefixes {} and {} differ",
+        first.prefix().to_hex_string(),
+        second.prefix().to_hex_string()
+      ));
+    }
+
+    let input = ASCIIData::from("input");
+    if first.query(&input) != second.query(&input) {
+      return Err("equally seeded oracles gave different output".to_string());
+    }
+
+    let other = PrefixOracle::new(inner, &mut StdRng::seed_from_u64(7331));
+    if other.prefix() == first.prefix() {
+      return Err("differently seeded oracles share a prefix".to_string());
+    }
+
+    return Ok(());
+  }
+}
@@ -0,0 +1,107 @@
+use crate::attack::oracle::*;
+use crate::data::*;
+
+// Single-byte biases in the RC4 keystream: Z16 (index 15) leans towards 240 and Z32 (index 31) towards 224.
+const BIASED_POSITIONS: [(usize, u8); 2] = [(15, 240), (31, 224)];
+
+// Encryptions requested per padding length by rc4_bias_attack. The real biases are tiny, so it takes millions of
+// samples to recover a byte reliably.
+pub const DEFAULT_SAMPLES: usize = 1 << 23;
+
+// Challenge 56, Set 7
+// Recovers a secret of known_len bytes (at most 32) that the oracle appends to our input before RC4-encrypting under a
+// fresh random key, using DEFAULT_SAMPLES encryptions per padding length.
+#[allow(dead_code)]
+pub fn rc4_bias_attack(oracle: &(impl Oracle + ?Sized), known_len: usize) -> ASCIIData {
+  rc4_bias_attack_with_samples(oracle, known_len, DEFAULT_SAMPLES)
+}
+
+// rc4_bias_attack with the number of encryptions per padding length given. For each secret byte, padding shifts it
+// under a biased keystream position; every sample then votes for ciphertext ^ bias, and the byte with the most votes
+// wins.
+#[allow(dead_code)]
+pub fn rc4_bias_attack_with_samples(oracle: &(impl Oracle + ?Sized), known_len: usize, samples: usize) -> ASCIIData {
+  if known_len > 32 {
+    panic!("Can only position 32 secret bytes under the Z16/Z32 biases (got {})", known_len);
+  }
+
+  let mut votes = vec![[0u64; 256]; known_len];
+
+  for padding in 0..32 {
+    let targets = Vec::from_iter(
+      BIASED_POSITIONS
+        .iter()
+        .filter(|(position, _)| *position >= padding && *position - padding < known_len)
+        .map(|&(position, bias)| (position, position - padding, bias))
+    );
+
+    if targets.is_empty() {
+      continue;
+    }
+
+    let request = ASCIIData::repeated(b'A', padding);
+    for _ in 0..samples {
+      let ciphertext = oracle.query(&request);
+      for &(position, secret_index, bias) in &targets {
+        votes[secret_index][(ciphertext.bytes()[position] ^ bias) as usize] += 1;
+      }
+    }
+  }
+
+  return ASCIIData::from_iter(votes.iter().map(|counts| (0..256).max_by_key(|&b| counts[b]).unwrap() as u8));
+}
+
+#[cfg(feature = "rand")]
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+  use crate::crypto::rc4::rc4;
+  use rand::Rng;
+
+  #[allow(dead_code)]
+  fn append_secret(request: &ASCIIData, secret: &ASCIIData) -> ASCIIData {
+    let mut plaintext = request.bytes().clone();
+    plaintext.extend(secret.bytes());
+    return ASCIIData::from(plaintext);
+  }
+
+  // A stand-in keystream with exaggerated Z16/Z32 biases, so the byte alignment and voting can be checked quickly.
+  #[test]
+  fn test_bias_voting() -> Result<(), String> {
+    let secret = ASCIIData::from("BE SURE TO DRINK YOUR OVALTINE!!");
+    let oracle = |request: &ASCIIData| {
+      let plaintext = append_secret(request, &secret);
+      let mut rng = rand::rng();
+      let mut keystream = ASCIIData::random(plaintext.len()).bytes().clone();
+      for &(position, bias) in &BIASED_POSITIONS {
+        if position < keystream.len() && rng.random_range(0..8) == 0 {
+          keystream[position] = bias;
+        }
+      }
+
+      return &plaintext ^ &ASCIIData::from(keystream);
+    };
+
+    let res = rc4_bias_attack_with_samples(&oracle, secret.len(), 1 << 12);
+    if res != secret {
+      return Err(format!("recovered {:?}", res.to_string()));
+    }
+
+    return Ok(());
+  }
+
+  // This takes DEFAULT_SAMPLES encryptions per padding length; run it with --release --ignored.
+  #[test]
+  #[ignore]
+  fn test_rc4_bias_attack() -> Result<(), String> {
+    let secret = ASCIIData::from("Wu");
+    let oracle = |request: &ASCIIData| rc4(&append_secret(request, &secret), &ASCIIData::random(16));
+
+    let res = rc4_bias_attack(&oracle, secret.len());
+    if res != secret {
+      return Err(format!("recovered {:?}", res.to_string()));
+    }
+
+    return Ok(());
+  }
+}
@@ -1,14 +1,69 @@
+use crate::attack::single_byte_xor::*;
 use crate::data::*;
 
+use std::cmp;
+use std::fmt::Display;
+use std::ops::Range;
+
+// An empty key cannot be repeated to any nonzero length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmptyKeyError;
+
+impl Display for EmptyKeyError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "repeating-key XOR needs a non-empty key")
+  }
+}
+
+impl std::error::Error for EmptyKeyError {}
+
+// The key is repeated as many times as needed to cover the message; a key longer than the message only has its first
+// message.len() bytes used.
+#[allow(dead_code)]
+pub fn encrypt_repeating_key_xor(message: &ASCIIData, key: ASCIIData) -> Result<ASCIIData, EmptyKeyError> {
+  if key.is_empty() {
+    return Err(EmptyKeyError);
+  }
+
+  return Ok(xor_cycled(message, &key));
+}
+
+// XOR is its own inverse, so this is encrypt_repeating_key_xor under a name that says what the call site is doing.
 #[allow(dead_code)]
-pub fn encrypt_repeating_key_xor(message: &ASCIIData, key: ASCIIData) -> ASCIIData {
-  message ^ &ASCIIData::from_iter(key.into_iter().cycle().take(message.len()))
+pub fn decrypt_repeating_key_xor(ciphertext: &ASCIIData, key: ASCIIData) -> Result<ASCIIData, EmptyKeyError> {
+  encrypt_repeating_key_xor(ciphertext, key)
+}
+
+// How close a keysize's score must come to one of its divisors' for it to be treated as that divisor repeated.
+const IC_DIVISOR_TOLERANCE: f32 = 0.9;
+
+// An alternative to ranking keysizes by normalized Hamming distance. Splits the ciphertext into keysize columns (every
+// keysize-th byte) and scores the keysize by the mean index of coincidence of those columns, best (highest) first. At
+// the true keysize every column is single-byte XORed text, but so is every column at a multiple of it, which can then
+// score slightly higher by chance; a keysize scoring within IC_DIVISOR_TOLERANCE of a divisor therefore takes that
+// divisor's score and is ranked right after it. Keysizes too large to give each column two bytes are skipped.
+#[allow(dead_code)]
+pub fn rank_keysizes_ic(ciphertext: &ASCIIData, range: Range<usize>) -> Vec<(usize, f32)> {
+  let scores = Vec::from_iter(range.filter(|&keysize| keysize > 0 && 2 * keysize <= ciphertext.len()).map(|keysize| {
+    let columns = (0..keysize).map(|i| ASCIIData::from_iter(ciphertext.iter().skip(i).step_by(keysize).copied()));
+    (keysize, columns.map(|column| index_of_coincidence(&column)).sum::<f32>() / keysize as f32)
+  }));
+
+  let mut ranked = Vec::from_iter(scores.iter().map(|&(keysize, score)| {
+    let divisor = scores.iter().find(|&&(d, s)| d < keysize && keysize % d == 0 && s >= IC_DIVISOR_TOLERANCE * score);
+    (keysize, divisor.map_or(score, |&(_, s)| s))
+  }));
+
+  ranked.sort_by(|(k1, s1), (k2, s2)| s2.partial_cmp(s1).unwrap_or(cmp::Ordering::Equal).then(k1.cmp(k2)));
+
+  return ranked;
 }
 
 #[allow(unused_imports)]
 mod tests {
   use super::*;
   use crate::data::ASCIIData;
+  use crate::util::*;
 
   #[test]
   fn test_encrypt_repeating_key_xor() -> Result<(), String> {
@@ -17,10 +72,67 @@ mod tests {
 
     let result = "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272".to_string()
       + "a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f";
-    let res = encrypt_repeating_key_xor(&message, key).to_hex_string();
+    let res = encrypt_repeating_key_xor(&message, key).map_err(|e| e.to_string())?;
+    return assert_data_eq(&res, &ASCIIData::from_hex(result));
+  }
+
+  #[test]
+  fn test_decrypt_repeating_key_xor() -> Result<(), String> {
+    let message = ASCIIData::from("Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal");
+    let key = ASCIIData::from("ICE");
+
+    let ciphertext = encrypt_repeating_key_xor(&message, key.clone()).map_err(|e| e.to_string())?;
+    let decrypted = decrypt_repeating_key_xor(&ciphertext, key).map_err(|e| e.to_string())?;
+    return assert_data_eq(&decrypted, &message);
+  }
+
+  #[test]
+  fn test_xor_cycled() -> Result<(), String> {
+    let message = ASCIIData::from("Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal");
+    let key = ASCIIData::from("ICE");
+
+    let expected = encrypt_repeating_key_xor(&message, key.clone()).map_err(|e| e.to_string())?;
+    return assert_data_eq(&xor_cycled(&message, &key), &expected);
+  }
+
+  #[test]
+  fn test_encrypt_empty_key() -> Result<(), String> {
+    let message = ASCIIData::from("Burning 'em");
+    if encrypt_repeating_key_xor(&message, ASCIIData::from("")) != Err(EmptyKeyError) {
+      return Err("empty key was accepted for encryption".to_string());
+    }
+
+    if decrypt_repeating_key_xor(&message, ASCIIData::from("")) != Err(EmptyKeyError) {
+      return Err("empty key was accepted for decryption".to_string());
+    }
+
+    return Ok(());
+  }
 
-    if res != result {
-      return Err(format!("wrong output: {}", res));
+  #[test]
+  fn test_encrypt_long_key() -> Result<(), String> {
+    let message = ASCIIData::from("Burning");
+    let long = encrypt_repeating_key_xor(&message, ASCIIData::from("ICEICEIunused")).map_err(|e| e.to_string())?;
+    let short = encrypt_repeating_key_xor(&message, ASCIIData::from("ICE")).map_err(|e| e.to_string())?;
+
+    return assert_data_eq(&long, &short);
+  }
+
+  #[test]
+  fn test_rank_keysizes_ic() -> Result<(), String> {
+    let message = ASCIIData::from(
+      "Ready for the whole thing? Here we go. It was a bright cold day in April, and the clocks were striking \
+       thirteen. Winston Smith, his chin nuzzled into his breast in an effort to escape the vile wind, slipped quickly \
+       through the glass doors of Victory Mansions, though not quickly enough to prevent a swirl of gritty dust from \
+       entering along with him. The hallway smelt of boiled cabbage and old rag mats. At one end of it a coloured \
+       poster, too large for indoor display, had been tacked to the wall."
+    );
+    for key in ["ICE", "Terminator X", "YELLOW SUBMARINE"] {
+      let ciphertext = encrypt_repeating_key_xor(&message, ASCIIData::from(key)).map_err(|e| e.to_string())?;
+      let ranked = rank_keysizes_ic(&ciphertext, 2..41);
+      if ranked[0].0 != key.len() {
+        return Err(format!("IC ranked keysizes for {:?} as {:?}", key, &ranked[..5]));
+      }
     }
 
     return Ok(());
@@ -0,0 +1,74 @@
+use num_bigint::{BigInt, BigUint, Sign};
+
+use crate::math::modular::*;
+use crate::math::roots::*;
+
+// Challenge 40, Set 5, for any public exponent e
+// Recovers a message sent unpadded to several recipients who all use exponent e, given (ciphertext, modulus) pairs.
+// CRT combines the ciphertexts into m^e modulo the product of the moduli; once that product exceeds m^e, which e
+// recipients guarantee since m is smaller than every modulus, the combined value is m^e itself and an exact e-th root
+// gives m back. None for e = 0 or fewer than e ciphertexts, if the congruences are inconsistent, or if the combined
+// value is not a perfect e-th power.
+#[allow(dead_code)]
+pub fn rsa_broadcast_attack_n(cts: &[(BigUint, BigUint)], e: u32) -> Option<BigUint> {
+  if e == 0 || cts.len() < e as usize {
+    return None;
+  }
+
+  let residues = Vec::from_iter(
+    cts.iter().map(|(c, n)| (BigInt::from_biguint(Sign::Plus, c.clone()), BigInt::from_biguint(Sign::Plus, n.clone())))
+  );
+
+  let (m, exact) = iroot(&crt(&residues)?.to_biguint()?, e);
+  return if exact { Some(m) } else { None };
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+  use crate::math::factor::*;
+
+  #[allow(dead_code)]
+  fn next_prime(n: &BigUint) -> BigUint {
+    let mut candidate = n + 1u32;
+    while !is_probable_prime(&candidate) {
+      candidate += 1u32;
+    }
+
+    return candidate;
+  }
+
+  #[test]
+  fn test_rsa_broadcast_attack_n() -> Result<(), String> {
+    let e = 5;
+    let mut prime = BigUint::from(1u32) << 96;
+    let moduli = Vec::from_iter((0..e).map(|_| {
+      let p = next_prime(&prime);
+      let q = next_prime(&p);
+      prime = q.clone();
+      &p * &q
+    }));
+
+    let message = BigUint::from_bytes_be(b"Ice ice baby, too cold.");
+    let cts = Vec::from_iter(moduli.iter().map(|n| (message.modpow(&BigUint::from(e), n), n.clone())));
+
+    let res = rsa_broadcast_attack_n(&cts, e);
+    if res != Some(message.clone()) {
+      return Err(format!("recovered {:?}", res));
+    }
+
+    for short in [&cts[..0], &cts[..4]] {
+      let res = rsa_broadcast_attack_n(short, e);
+      if res.is_some() {
+        return Err(format!("recovered {:?} from {} ciphertexts", res, short.len()));
+      }
+    }
+
+    let res = rsa_broadcast_attack_n(&cts, 0);
+    if res.is_some() {
+      return Err(format!("recovered {:?} with e = 0", res));
+    }
+
+    return Ok(());
+  }
+}
@@ -1,23 +1,68 @@
 use crate::data::*;
 use std::cmp;
 
-#[allow(dead_code)]
-const ENGLISH_CHAR_FREQUENCIES: [f32; 27] = [
-  0.0653, 0.0126, 0.0223, 0.0328, 0.1027, 0.0198, 0.0162, 0.0498, 0.0567, 0.0010, 0.0056, 0.0332, 0.0203, 0.0517,
-  0.0616, 0.0150, 0.0008, 0.0499, 0.0532, 0.0752, 0.0228, 0.0080, 0.0170, 0.0014, 0.0143, 0.0005, 0.1823
-];
+// Expected frequencies of 'a' through 'z' (case-insensitive) followed by space, as fractions of all characters.
+#[derive(Clone, Copy)]
+pub struct LanguageProfile {
+  pub frequencies: [f32; 27]
+}
 
 #[allow(dead_code)]
-pub fn freq_and_alphabet_score(data: &ASCIIData) -> f32 {
-  fn uppercase(b: usize) -> bool { 65 <= b && b <= 90 }
-  fn lowercase(b: usize) -> bool { 97 <= b && b <= 122 }
+impl LanguageProfile {
+  pub const ENGLISH: LanguageProfile = LanguageProfile {
+    frequencies: [
+      0.0653, 0.0126, 0.0223, 0.0328, 0.1027, 0.0198, 0.0162, 0.0498, 0.0567, 0.0010, 0.0056, 0.0332, 0.0203, 0.0517,
+      0.0616, 0.0150, 0.0008, 0.0499, 0.0532, 0.0752, 0.0228, 0.0080, 0.0170, 0.0014, 0.0143, 0.0005, 0.1823
+    ]
+  };
 
-  let mut counts = vec![0u64; 256];
+  // Accented letters are folded out, since only ASCII is scored.
+  pub const FRENCH: LanguageProfile = LanguageProfile {
+    frequencies: [
+      0.0644, 0.0076, 0.0275, 0.0309, 0.1241, 0.0090, 0.0073, 0.0062, 0.0635, 0.0052, 0.0006, 0.0460, 0.0250, 0.0598,
+      0.0489, 0.0213, 0.0115, 0.0564, 0.0670, 0.0611, 0.0532, 0.0155, 0.0004, 0.0036, 0.0011, 0.0027, 0.1800
+    ]
+  };
 
-  for &b in data.bytes() {
-    counts[b as usize] += 1
+  // Umlauts and the eszett are folded out, since only ASCII is scored.
+  pub const GERMAN: LanguageProfile = LanguageProfile {
+    frequencies: [
+      0.0547, 0.0158, 0.0229, 0.0426, 0.1376, 0.0139, 0.0253, 0.0384, 0.0550, 0.0022, 0.0119, 0.0289, 0.0213, 0.0821,
+      0.0218, 0.0056, 0.0002, 0.0588, 0.0610, 0.0517, 0.0350, 0.0071, 0.0161, 0.0003, 0.0003, 0.0095, 0.1800
+    ]
+  };
+}
+
+impl Default for LanguageProfile {
+  fn default() -> Self { Self::ENGLISH }
+}
+
+// Builds a profile from sample text by counting letters (case-insensitively) and spaces. Everything else is ignored,
+// so the frequencies are fractions of the letters and spaces only. A corpus with none of either gives all zeros.
+#[allow(dead_code)]
+pub fn profile_from_corpus(text: &str) -> LanguageProfile {
+  let mut counts = [0u64; 27];
+  for b in text.bytes() {
+    if b.is_ascii_alphabetic() {
+      counts[(b.to_ascii_lowercase() - 97) as usize] += 1;
+    } else if b == 32 {
+      counts[26] += 1;
+    }
   }
 
+  let total = counts.iter().sum::<u64>().max(1) as f32;
+  return LanguageProfile { frequencies: counts.map(|count| count as f32 / total) };
+}
+
+#[allow(dead_code)]
+pub fn freq_and_alphabet_score(data: &ASCIIData) -> f32 { freq_and_alphabet_score_for(data, &LanguageProfile::ENGLISH) }
+
+#[allow(dead_code)]
+pub fn freq_and_alphabet_score_for(data: &ASCIIData, profile: &LanguageProfile) -> f32 {
+  fn uppercase(b: usize) -> bool { 65 <= b && b <= 90 }
+  fn lowercase(b: usize) -> bool { 97 <= b && b <= 122 }
+
+  let counts = data.byte_histogram();
   let mut norm_squared = 0.0;
 
   for i in 0..256 {
@@ -31,7 +76,7 @@ pub fn freq_and_alphabet_score(data: &ASCIIData) -> f32 {
         i - 97
       };
 
-      diff -= ENGLISH_CHAR_FREQUENCIES[j] * (data.len() as f32);
+      diff -= profile.frequencies[j] * (data.len() as f32);
     }
 
     norm_squared += diff * diff;
@@ -40,35 +85,431 @@ pub fn freq_and_alphabet_score(data: &ASCIIData) -> f32 {
   return norm_squared;
 }
 
+// freq_and_alphabet_score on a scale that doesn't depend on the length, so texts of different lengths can be compared.
+// The raw score sums squared count differences, which grow with the square of the length, so that's what it's divided
+// by (in effect comparing frequencies instead of counts). Empty data has no frequencies to compare and scores worst,
+// f32::INFINITY, so a blank line never beats real text.
+#[allow(dead_code)]
+pub fn normalized_freq_score(data: &ASCIIData) -> f32 {
+  if data.is_empty() {
+    return f32::INFINITY;
+  }
+
+  return freq_and_alphabet_score(data) / (data.len() * data.len()) as f32;
+}
+
+// Expected share of bytes that are neither letters nor spaces (digits, punctuation, newlines) in English text.
+#[allow(dead_code)]
+const ENGLISH_OTHER_FREQUENCY: f32 = 0.02;
+
+// Pearson's chi-squared statistic of the letter and space counts against English, with every other byte lumped into a
+// single penalty bucket, so lower is better. Empty data carries no evidence of being English and scores infinity (the
+// statistic itself would be 0/0).
+#[allow(dead_code)]
+pub fn chi_squared_score(data: &ASCIIData) -> f32 {
+  if data.is_empty() {
+    return f32::INFINITY;
+  }
+
+  let mut counts = [0u64; 28];
+
+  for &b in data.bytes() {
+    let bucket = if b.is_ascii_alphabetic() {
+      (b.to_ascii_lowercase() - 97) as usize
+    } else if b == 32 {
+      26
+    } else {
+      27
+    };
+
+    counts[bucket] += 1
+  }
+
+  let n = data.len() as f32;
+  let mut chi_squared = 0.0;
+
+  for (i, &count) in counts.iter().enumerate() {
+    let frequency = if i < 27 { LanguageProfile::ENGLISH.frequencies[i] } else { ENGLISH_OTHER_FREQUENCY };
+    let expected = frequency * n;
+    let diff = count as f32 - expected;
+    chi_squared += diff * diff / expected;
+  }
+
+  return chi_squared;
+}
+
+// Relative frequencies (as fractions of all letter n-grams) of the most common English bigrams and trigrams.
+#[allow(dead_code)]
+const ENGLISH_BIGRAM_FREQUENCIES: [(&[u8; 2], f32); 30] = [
+  (b"TH", 0.0356),
+  (b"HE", 0.0307),
+  (b"IN", 0.0243),
+  (b"ER", 0.0205),
+  (b"AN", 0.0199),
+  (b"RE", 0.0185),
+  (b"ON", 0.0176),
+  (b"AT", 0.0149),
+  (b"EN", 0.0145),
+  (b"ND", 0.0135),
+  (b"TI", 0.0134),
+  (b"ES", 0.0134),
+  (b"OR", 0.0128),
+  (b"TE", 0.0120),
+  (b"OF", 0.0117),
+  (b"ED", 0.0117),
+  (b"IS", 0.0113),
+  (b"IT", 0.0112),
+  (b"AL", 0.0109),
+  (b"AR", 0.0107),
+  (b"ST", 0.0105),
+  (b"TO", 0.0104),
+  (b"NT", 0.0104),
+  (b"NG", 0.0095),
+  (b"SE", 0.0093),
+  (b"HA", 0.0093),
+  (b"AS", 0.0087),
+  (b"OU", 0.0087),
+  (b"IO", 0.0083),
+  (b"LE", 0.0083)
+];
+
+#[allow(dead_code)]
+const ENGLISH_TRIGRAM_FREQUENCIES: [(&[u8; 3], f32); 30] = [
+  (b"THE", 0.0181),
+  (b"AND", 0.0073),
+  (b"ING", 0.0072),
+  (b"ENT", 0.0042),
+  (b"ION", 0.0042),
+  (b"HER", 0.0036),
+  (b"FOR", 0.0034),
+  (b"THA", 0.0033),
+  (b"NTH", 0.0033),
+  (b"INT", 0.0032),
+  (b"ERE", 0.0031),
+  (b"TIO", 0.0031),
+  (b"TER", 0.0030),
+  (b"EST", 0.0028),
+  (b"ERS", 0.0028),
+  (b"ATI", 0.0026),
+  (b"HAT", 0.0026),
+  (b"ATE", 0.0025),
+  (b"ALL", 0.0025),
+  (b"ETH", 0.0024),
+  (b"HES", 0.0024),
+  (b"VER", 0.0024),
+  (b"HIS", 0.0024),
+  (b"OFT", 0.0022),
+  (b"ITH", 0.0021),
+  (b"FTH", 0.0021),
+  (b"STH", 0.0021),
+  (b"OTH", 0.0021),
+  (b"RES", 0.0021),
+  (b"ONT", 0.0020)
+];
+
+// Smoothing probability given to letter n-grams missing from the tables above.
+#[allow(dead_code)]
+const NGRAM_SMOOTHING: f32 = 1e-4;
+
+// Probabilities for n-grams that are not purely letters: those spanning a word boundary, those containing digits or
+// punctuation, and those containing unprintable bytes.
+#[allow(dead_code)]
+const NGRAM_WORD_BOUNDARY: f32 = 1e-3;
+
+#[allow(dead_code)]
+const NGRAM_PUNCTUATION: f32 = 1e-5;
+
+#[allow(dead_code)]
+const NGRAM_UNPRINTABLE: f32 = 1e-8;
+
+// The n-gram lengths there are tables for, so that a Scorer can't be set up with one ngram_score would reject.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NgramSize {
+  Bigram,
+  Trigram
+}
+
+impl NgramSize {
+  pub fn window_len(self) -> usize {
+    match self {
+      NgramSize::Bigram => 2,
+      NgramSize::Trigram => 3
+    }
+  }
+
+  // ngram_score with a size it always has a table for.
+  fn score(self, data: &ASCIIData) -> f32 { ngram_score(data, self.window_len()).unwrap_or(f32::INFINITY) }
+}
+
+// Negative log-likelihood of the data under the bigram (n = 2) or trigram (n = 3) model, so lower is better, like
+// freq_and_alphabet_score. None for any other n, which there is no table for.
+#[allow(dead_code)]
+pub fn ngram_score(data: &ASCIIData, n: usize) -> Option<f32> {
+  fn table_lookup(window: &[u8]) -> Option<f32> {
+    let upper = Vec::from_iter(window.iter().map(|b| b.to_ascii_uppercase()));
+    if window.len() == 2 {
+      ENGLISH_BIGRAM_FREQUENCIES.iter().find(|(gram, _)| gram[..] == upper[..]).map(|(_, f)| *f)
+    } else {
+      ENGLISH_TRIGRAM_FREQUENCIES.iter().find(|(gram, _)| gram[..] == upper[..]).map(|(_, f)| *f)
+    }
+  }
+
+  if n != 2 && n != 3 {
+    return None;
+  }
+
+  let mut neg_log_likelihood = 0.0;
+
+  for window in data.bytes().windows(n) {
+    let probability = if window.iter().all(|b| b.is_ascii_alphabetic()) {
+      table_lookup(window).unwrap_or(NGRAM_SMOOTHING)
+    } else if window.iter().all(|b| b.is_ascii_alphabetic() || *b == b' ') {
+      NGRAM_WORD_BOUNDARY
+    } else if window.iter().all(|b| b.is_ascii_graphic() || b.is_ascii_whitespace()) {
+      NGRAM_PUNCTUATION
+    } else {
+      NGRAM_UNPRINTABLE
+    };
+
+    neg_log_likelihood -= probability.log10();
+  }
+
+  return Some(neg_log_likelihood);
+}
+
+// The fraction of bytes that are printable ASCII or common whitespace (tab, newline, carriage return). Empty data
+// counts as fully printable.
+#[allow(dead_code)]
+pub fn printable_ratio(data: &ASCIIData) -> f32 {
+  if data.is_empty() {
+    return 1.0;
+  }
+
+  let printable = data.iter().filter(|&&b| (32..127).contains(&b) || b == b'\t' || b == b'\n' || b == b'\r').count();
+  return printable as f32 / data.len() as f32;
+}
+
+// The chance that two bytes drawn from data without replacement are equal, i.e. the sum of n_i (n_i - 1) / (N (N - 1))
+// over byte counts. English text scores around 0.07, uniformly random bytes about 1/256. XORing with a single byte only
+// permutes the byte values, so it leaves the index unchanged. Data shorter than two bytes scores 0.0.
+#[allow(dead_code)]
+pub fn index_of_coincidence(data: &ASCIIData) -> f32 {
+  if data.len() < 2 {
+    return 0.0;
+  }
+
+  let n = data.len() as f32;
+  return data.byte_histogram().iter().map(|&c| (c * c.saturating_sub(1)) as f32).sum::<f32>() / (n * (n - 1.0));
+}
+
+// Share of printable bytes above which data is taken to be text.
+pub const TEXT_PRINTABLE_THRESHOLD: f32 = 0.9;
+
+// A cheap check, independent of letter frequencies, for pruning candidates before scoring them properly.
+#[allow(dead_code)]
+pub fn looks_like_text(data: &ASCIIData) -> bool { printable_ratio(data) >= TEXT_PRINTABLE_THRESHOLD }
+
+#[allow(dead_code)]
+#[derive(Clone, Copy)]
+pub enum Scorer {
+  // Single-character frequencies in the given language via freq_and_alphabet_score_for.
+  Frequency(LanguageProfile),
+
+  // Letter and space counts normalized by expected frequency via chi_squared_score.
+  ChiSquared,
+
+  // Bigram or trigram log-likelihood via ngram_score; better than Frequency on short ciphertexts.
+  Ngram(NgramSize),
+
+  // The sum of ngram_score and freq_and_alphabet_score_for divided by the length, which puts the two on a comparable
+  // per-byte scale, so the n-grams can settle short ciphertexts while the unigram frequencies still count.
+  FrequencyAndNgram(LanguageProfile, NgramSize)
+}
+
+impl Default for Scorer {
+  fn default() -> Self { Scorer::Frequency(LanguageProfile::ENGLISH) }
+}
+
+impl Scorer {
+  pub fn score(&self, data: &ASCIIData) -> f32 {
+    match self {
+      Scorer::Frequency(profile) => freq_and_alphabet_score_for(data, profile),
+      Scorer::ChiSquared => chi_squared_score(data),
+      Scorer::Ngram(n) => n.score(data),
+      Scorer::FrequencyAndNgram(profile, n) => {
+        let per_byte = if data.is_empty() { 0.0 } else { 1.0 / data.len() as f32 };
+        per_byte * freq_and_alphabet_score_for(data, profile) + n.score(data)
+      }
+    }
+  }
+}
+
 #[allow(dead_code)]
 pub fn attack_single_byte_xor(ciphertext: &ASCIIData) -> (u8, f32, ASCIIData) {
-  let n = ciphertext.len();
-  let build_key = |character| ASCIIData::from(vec![character; n]);
-  let argmax = (0..256u16)
-    .into_iter()
-    .zip(0..256u16)
-    .map(|(arg, i)| (arg, ciphertext ^ &build_key(i as u8)))
-    .map(|(arg, msg)| (arg as u8, freq_and_alphabet_score(&msg), msg))
-    .min_by(|(_, s1, _), (_, s2, _)| s1.partial_cmp(s2).unwrap_or(cmp::Ordering::Equal));
+  attack_single_byte_xor_with(ciphertext, Scorer::default())
+}
+
+// For ciphertexts still in hex: decodes them to raw bytes first, since a HexData holds one nibble per byte.
+#[allow(dead_code)]
+pub fn attack_single_byte_xor_hex(ciphertext: &HexData) -> (u8, f32, ASCIIData) {
+  attack_single_byte_xor(&ASCIIData::from_hex_data(ciphertext.clone()))
+}
+
+#[allow(dead_code)]
+pub fn attack_single_byte_xor_with(ciphertext: &ASCIIData, scorer: Scorer) -> (u8, f32, ASCIIData) {
+  attack_single_byte_xor_top_n_with(ciphertext, 1, scorer).remove(0)
+}
+
+#[allow(dead_code)]
+pub fn attack_single_byte_xor_top_n(ciphertext: &ASCIIData, n: usize) -> Vec<(u8, f32, ASCIIData)> {
+  attack_single_byte_xor_top_n_with(ciphertext, n, Scorer::default())
+}
+
+// Returns the n best (key, score, message) candidates, best first. Ties keep the smaller key first.
+#[allow(dead_code)]
+pub fn attack_single_byte_xor_top_n_with(
+  ciphertext: &ASCIIData,
+  n: usize,
+  scorer: Scorer
+) -> Vec<(u8, f32, ASCIIData)> {
+  let mut candidates = Vec::from_iter((0..=255u8).map(|key| {
+    let msg = ciphertext.xor_with_byte(key);
+    (key, scorer.score(&msg), msg)
+  }));
+
+  candidates.sort_by(|(_, s1, _), (_, s2, _)| s1.partial_cmp(s2).unwrap_or(cmp::Ordering::Equal));
+  candidates.truncate(n);
 
-  return argmax.unwrap();
+  return candidates;
+}
+
+// Challenge 4, Set 1: runs the single-byte XOR attack on every line and keeps the one whose decryption scores best
+// (preferring lines that decrypt to something that looks like text, and normalizing for length), returning its index
+// along with the recovered key and message. Panics if there are no lines.
+#[allow(dead_code)]
+pub fn find_xored_line(lines: &[ASCIIData]) -> (usize, u8, ASCIIData) {
+  let mut best: Option<(usize, u8, f32, ASCIIData)> = None;
+  for (i, line) in lines.iter().enumerate() {
+    let (key, _, msg) = attack_single_byte_xor(line);
+    let score = normalized_freq_score(&msg);
+    let better = best.as_ref().is_none_or(|(_, _, best_score, best_msg)| {
+      (looks_like_text(&msg), -score) > (looks_like_text(best_msg), -best_score)
+    });
+
+    if better {
+      best = Some((i, key, score, msg));
+    }
+  }
+
+  let (index, key, _, msg) = best.expect("Cannot search an empty list of lines");
+  return (index, key, msg);
 }
 
 #[allow(unused_imports)]
 mod tests {
   use super::*;
   use crate::data::ASCIIData;
-
-  use std::fs::File;
-  use std::io::{BufRead, BufReader};
+  use crate::fixtures::*;
+  use crate::util::*;
 
   // Challenge 3, Set 1
   #[test]
   fn test_attack() -> Result<(), String> {
     let ciphertext = "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736";
     let (key, _, msg) = attack_single_byte_xor(&ASCIIData::from_hex(ciphertext));
-    if key != 88 || msg.to_string() != "Cooking MC's like a pound of bacon".to_string() {
-      return Err(format!("Attack failed; key was {} with message {}", key, msg));
+    if key != 88 {
+      return Err(format!("Attack picked key {}", key));
+    }
+
+    return assert_data_eq(&msg, &ASCIIData::from("Cooking MC's like a pound of bacon"));
+  }
+
+  #[test]
+  fn test_attack_hex() -> Result<(), String> {
+    let ciphertext = HexData::from("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736");
+    let (key, _, msg) = attack_single_byte_xor_hex(&ciphertext);
+    if key != 88 {
+      return Err(format!("Attack picked key {}", key));
+    }
+
+    return assert_data_eq(&msg, &ASCIIData::from("Cooking MC's like a pound of bacon"));
+  }
+
+  #[test]
+  fn test_profile_from_corpus() -> Result<(), String> {
+    let corpus = "It was the best of times, it was the worst of times, it was the age of wisdom, it was the age of \
+                  foolishness, it was the epoch of belief, it was the epoch of incredulity, it was the season of \
+                  Light, it was the season of Darkness, it was the spring of hope, it was the winter of despair.";
+    let profile = profile_from_corpus(corpus);
+
+    let mut ranked = Vec::from_iter(0..27);
+    ranked.sort_by(|&i, &j| profile.frequencies[j].total_cmp(&profile.frequencies[i]));
+    if ranked[..2] != [26, 4] {
+      return Err(format!("highest weights are at {:?}", &ranked[..2]));
+    }
+
+    let total: f32 = profile.frequencies.iter().sum();
+    if (total - 1.0).abs() > 1e-4 {
+      return Err(format!("frequencies sum to {}", total));
+    }
+
+    let ciphertext = &ASCIIData::from("it was the age of wisdom") ^ &ASCIIData::repeated(42, 24);
+    let (key, _, _) = attack_single_byte_xor_with(&ciphertext, Scorer::Frequency(profile));
+    if key != 42 {
+      return Err(format!("custom profile recovered key {}", key));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_normalized_freq_score() -> Result<(), String> {
+    let english = ASCIIData::from("Now that the party is jumping\n");
+    let noise = ASCIIData::from("zq");
+
+    if freq_and_alphabet_score(&english) < freq_and_alphabet_score(&noise) {
+      return Err("the raw score no longer favours short noise; pick a harder case".to_string());
+    }
+
+    if normalized_freq_score(&english) >= normalized_freq_score(&noise) {
+      return Err(format!(
+        "normalized scores: {} for English, {} for noise",
+        normalized_freq_score(&english),
+        normalized_freq_score(&noise)
+      ));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  #[cfg(feature = "rand")]
+  fn test_index_of_coincidence() -> Result<(), String> {
+    let english = ASCIIData::from(
+      "It was the best of times, it was the worst of times, it was the age of wisdom, it was the age of foolishness"
+    );
+    let random = ASCIIData::random(4096);
+
+    let (ic_english, ic_xored, ic_random) =
+      (index_of_coincidence(&english), index_of_coincidence(&english.xor_with_byte(77)), index_of_coincidence(&random));
+    if ic_english < 5.0 * ic_random || ic_english != ic_xored {
+      return Err(format!("IC of {} for English, {} XORed and {} for random bytes", ic_english, ic_xored, ic_random));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_looks_like_text() -> Result<(), String> {
+    let sentence = ASCIIData::from("Now that the party is jumping\n\twith the bass kicked in\r\n");
+    if printable_ratio(&sentence) != 1.0 || !looks_like_text(&sentence) {
+      return Err(format!("sentence has printable ratio {}", printable_ratio(&sentence)));
+    }
+
+    let noise = ASCIIData::from_iter((0..=255u8).rev());
+    if looks_like_text(&noise) {
+      return Err(format!("noise has printable ratio {}", printable_ratio(&noise)));
     }
 
     return Ok(());
@@ -77,24 +518,147 @@ mod tests {
   // Chalenge 4, Set 1
   #[test]
   fn test_attack_multiple() -> Result<(), String> {
-    let lines = BufReader::new(File::open("files/c4s1.txt").unwrap()).lines();
-
-    let mut best = None;
-    for line in lines {
-      let ciphertext = line.unwrap();
-      let result = attack_single_byte_xor(&ASCIIData::from_hex(ciphertext));
-      if let Some((_, best_score, _)) = best {
-        if result.1 < best_score {
-          best = Some(result)
-        }
-      } else {
-        best = Some(result)
+    let lines = load_lines("c4s1.txt").map_err(|e| e.to_string())?;
+    let ciphertexts = Vec::from_iter(lines.iter().map(ASCIIData::from_hex));
+
+    let (index, key, msg) = find_xored_line(&ciphertexts);
+    if index != 170 {
+      return Err(format!("Attack picked line {}", index));
+    }
+
+    if key != 53 {
+      return Err(format!("Attack picked key {}", key));
+    }
+
+    return assert_data_eq(&msg, &ASCIIData::from("Now that the party is jumping\n"));
+  }
+
+  #[test]
+  fn test_find_xored_line_blank_line() -> Result<(), String> {
+    let ciphertext = ASCIIData::from_hex("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736");
+    let (index, key, msg) = find_xored_line(&[ASCIIData::from(""), ciphertext]);
+    if index != 1 || key != 88 {
+      return Err(format!("Attack picked line {} with key {}", index, key));
+    }
+
+    return assert_data_eq(&msg, &ASCIIData::from("Cooking MC's like a pound of bacon"));
+  }
+
+  #[test]
+  fn test_attack_top_n() -> Result<(), String> {
+    let ciphertext = "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736";
+    let candidates = attack_single_byte_xor_top_n(&ASCIIData::from_hex(ciphertext), 3);
+    if candidates.len() != 3 {
+      return Err(format!("Expected 3 candidates, got {}", candidates.len()));
+    }
+
+    if !candidates.windows(2).all(|pair| pair[0].1 <= pair[1].1) {
+      return Err("Candidates are not sorted by score".to_string());
+    }
+
+    if !candidates.iter().any(|(key, _, msg)| *key == 88 && msg.to_string() == "Cooking MC's like a pound of bacon") {
+      return Err("Challenge 3 answer is not among the top 3 candidates".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_chi_squared_attack() -> Result<(), String> {
+    let ciphertext = ASCIIData::from_hex("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736");
+
+    for scorer in [Scorer::default(), Scorer::ChiSquared] {
+      let (key, _, msg) = attack_single_byte_xor_with(&ciphertext, scorer);
+      if key != 88 {
+        return Err(format!("Attack picked key {}", key));
+      }
+
+      assert_data_eq(&msg, &ASCIIData::from("Cooking MC's like a pound of bacon"))?;
+    }
+
+    let english = ASCIIData::from("Cooking MC's like a pound of bacon");
+    let shifted = &english ^ &ASCIIData::from(vec![32; english.len()]);
+    if chi_squared_score(&english) >= chi_squared_score(&shifted) {
+      return Err("Chi-squared scorer prefers the case-flipped candidate".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_chi_squared_empty() -> Result<(), String> {
+    let res = chi_squared_score(&ASCIIData::from(""));
+    if res != f32::INFINITY {
+      return Err(format!("empty data scored {}", res));
+    }
+
+    if chi_squared_score(&ASCIIData::from("a")) >= res {
+      return Err("a single letter does not beat empty data".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_attack_french() -> Result<(), String> {
+    let key = 66;
+    let ciphertext = ASCIIData::from("musique") ^ ASCIIData::from(vec![key; 7]);
+
+    let (english_key, _, _) = attack_single_byte_xor_with(&ciphertext, Scorer::Frequency(LanguageProfile::ENGLISH));
+    if english_key == key {
+      return Err("Expected the English profile to miss the key".to_string());
+    }
+
+    let (french_key, _, msg) = attack_single_byte_xor_with(&ciphertext, Scorer::Frequency(LanguageProfile::FRENCH));
+    if french_key != key {
+      return Err(format!("Attack picked key {}", french_key));
+    }
+
+    return assert_data_eq(&msg, &ASCIIData::from("musique"));
+  }
+
+  #[test]
+  fn test_trigram_beats_unigram() -> Result<(), String> {
+    // A single short word gives the unigram scorer too little to work with; its trigrams still give it away.
+    let key = 66;
+    let ciphertext = ASCIIData::from("thinking") ^ ASCIIData::from(vec![key; 8]);
+
+    let (unigram_key, _, _) = attack_single_byte_xor_with(&ciphertext, Scorer::default());
+    if unigram_key == key {
+      return Err("Expected the unigram scorer to miss the key".to_string());
+    }
+
+    let combined = Scorer::FrequencyAndNgram(LanguageProfile::ENGLISH, NgramSize::Trigram);
+    for scorer in [Scorer::Ngram(NgramSize::Trigram), combined] {
+      let (res_key, _, msg) = attack_single_byte_xor_with(&ciphertext, scorer);
+      if res_key != key {
+        return Err(format!("Attack picked key {}", res_key));
+      }
+
+      assert_data_eq(&msg, &ASCIIData::from("thinking"))?;
+    }
+
+    for n in [0, 1, 4] {
+      if ngram_score(&ASCIIData::from("thinking"), n).is_some() {
+        return Err(format!("ngram_score accepted n = {}", n));
       }
     }
 
-    let (key, _, msg) = best.unwrap();
-    if key != 53 || msg.to_string() != "Now that the party is jumping\n".to_string() {
-      return Err(format!("Attack failed; key was {} with message {}", key, msg));
+    return Ok(());
+  }
+
+  #[test]
+  fn test_frequency_and_ngram_attack() -> Result<(), String> {
+    let ciphertext = ASCIIData::from_hex("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736");
+
+    for n in [NgramSize::Bigram, NgramSize::Trigram] {
+      let scorer = Scorer::FrequencyAndNgram(LanguageProfile::ENGLISH, n);
+      let (key, _, msg) = attack_single_byte_xor_with(&ciphertext, scorer);
+      if key != 88 {
+        return Err(format!("Attack with {:?} picked key {}", n, key));
+      }
+
+      assert_data_eq(&msg, &ASCIIData::from("Cooking MC's like a pound of bacon"))?;
     }
 
     return Ok(());
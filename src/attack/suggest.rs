@@ -0,0 +1,92 @@
+use crate::attack::ecb::*;
+use crate::attack::repeating_key_xor::*;
+use crate::attack::single_byte_xor::*;
+use crate::data::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackHint {
+  SingleByteXor,
+  RepeatingKeyXor { keysize: usize },
+  AesEcb,
+  Unknown
+}
+
+// Index of coincidence above which data (or a keysize column of it) is taken to be text XORed with a single byte.
+// English sits around 0.07 and random bytes around 0.004.
+const TEXT_IC_THRESHOLD: f32 = 0.05;
+
+// Keysizes are only tried while each column still gets this many bytes; shorter columns give too noisy an index.
+const MIN_COLUMN_LEN: usize = 8;
+
+const MAX_KEYSIZE: usize = 40;
+
+// A guess at which attack in this crate fits a ciphertext, using only cheap statistics:
+//  - any repeated 16-byte block means ECB;
+//  - a text-like index of coincidence whose best single-byte XOR key gives printable output means single-byte XOR;
+//  - a keysize whose columns each look like that means repeating-key XOR with that keysize.
+// Anything else, including ciphertext from a decent stream or block cipher mode, is Unknown. The checks are only
+// advisory: short ciphertexts in particular can be misclassified.
+#[allow(dead_code)]
+pub fn suggest_attack(data: &ASCIIData) -> AttackHint {
+  if data.len() < 2 {
+    return AttackHint::Unknown;
+  }
+
+  if count_repeated_blocks(data, 16).is_some_and(|repeats| repeats > 0) {
+    return AttackHint::AesEcb;
+  }
+
+  if index_of_coincidence(data) >= TEXT_IC_THRESHOLD && looks_like_text(&attack_single_byte_xor(data).2) {
+    return AttackHint::SingleByteXor;
+  }
+
+  let max_keysize = MAX_KEYSIZE.min(data.len() / MIN_COLUMN_LEN);
+  if let Some(&(keysize, score)) = rank_keysizes_ic(data, 2..max_keysize + 1).first() {
+    if score >= TEXT_IC_THRESHOLD {
+      return AttackHint::RepeatingKeyXor { keysize };
+    }
+  }
+
+  return AttackHint::Unknown;
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+  use crate::crypto::rc4::*;
+
+  #[allow(dead_code)]
+  const SAMPLE: &str =
+    "I'm back and I'm ringin' the bell. A rockin' on the mike while the fly girls yell. In ecstasy in \
+    the back of me. Well that's my DJ Deshay cuttin' all them Z's. Hittin' hard and the girlies \
+    goin' crazy. Vanilla's on the mike, man I'm not lazy.";
+
+  #[test]
+  fn test_suggest_attack() -> Result<(), String> {
+    let sample = ASCIIData::from(SAMPLE);
+    let random = Rc4::new(b"suggest").keystream(sample.len());
+    let ecb_block = Rc4::new(b"block").keystream(16);
+
+    let mut ecb = random.truncate(64);
+    ecb.extend(&ecb_block);
+    ecb.extend(&random.slice(64..96));
+    ecb.extend(&ecb_block);
+
+    let repeating = encrypt_repeating_key_xor(&sample, ASCIIData::from("ICEBOX")).map_err(|e| e.to_string())?;
+    let cases = [
+      ("single-byte XOR", sample.xor_with_byte(0x5a), AttackHint::SingleByteXor),
+      ("repeating-key XOR", repeating, AttackHint::RepeatingKeyXor { keysize: 6 }),
+      ("ECB", ecb, AttackHint::AesEcb),
+      ("random", random, AttackHint::Unknown)
+    ];
+
+    for (name, data, expected) in cases {
+      let res = suggest_attack(&data);
+      if res != expected {
+        return Err(format!("suggested {:?} instead of {:?} for {}", res, expected, name));
+      }
+    }
+
+    return Ok(());
+  }
+}
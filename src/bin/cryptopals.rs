@@ -0,0 +1,86 @@
+#![allow(clippy::needless_return)]
+
+use std::{env, fs, process};
+
+use cryptopals::attack::ecb::*;
+use cryptopals::attack::repeating_key_xor::*;
+use cryptopals::attack::single_byte_xor::*;
+use cryptopals::data::*;
+
+const USAGE: &str = "usage: cryptopals <challenge> <input file> [key]";
+
+// ASCIIData::from_hex unwraps, which is fine for fixtures but not for whatever file the user points us at.
+fn decode_hex(line: &str) -> Result<ASCIIData, String> {
+  let bytes = hex::decode(line.trim()).map_err(|e| format!("invalid hex '{}': {}", line.trim(), e))?;
+  return Ok(ASCIIData::from(bytes));
+}
+
+// Challenge 3, Set 1: the file holds a single hex-encoded ciphertext.
+fn single_byte_xor(input: &str) -> Result<(), String> {
+  let ciphertext = decode_hex(input)?;
+  if ciphertext.is_empty() {
+    return Err("input file is empty".to_string());
+  }
+
+  let (key, _, msg) = attack_single_byte_xor(&ciphertext);
+  println!("key: {}\nmessage: {}", key, msg);
+  return Ok(());
+}
+
+// Challenge 4, Set 1: the file holds one hex-encoded ciphertext per line, exactly one of them single-byte XORed.
+fn detect_single_byte_xor(input: &str) -> Result<(), String> {
+  let lines = input.lines().map(decode_hex).collect::<Result<Vec<_>, _>>()?;
+  if lines.is_empty() {
+    return Err("input file is empty".to_string());
+  }
+
+  let (line, key, msg) = find_xored_line(&lines);
+  println!("line: {}\nkey: {}\nmessage: {}", line, key, msg);
+  return Ok(());
+}
+
+// Challenge 5, Set 1: the file holds the plaintext and the key is given on the command line.
+fn repeating_key_xor(input: &str, key: Option<&String>) -> Result<(), String> {
+  let key = key.ok_or("challenge 5 needs a key argument")?;
+  let ciphertext = encrypt_repeating_key_xor(&ASCIIData::from(input), ASCIIData::from(key.as_str()));
+  println!("{}", ciphertext.map_err(|e| e.to_string())?.to_hex_string());
+  return Ok(());
+}
+
+// Challenge 8, Set 1: the file holds one hex-encoded ciphertext per line, and the one with the most repeated 16-byte
+// blocks is the one encrypted with ECB.
+fn detect_ecb(input: &str) -> Result<(), String> {
+  let lines = input.lines().map(decode_hex).collect::<Result<Vec<_>, _>>()?;
+  let repeats = Vec::from_iter(lines.iter().map(|line| count_repeated_blocks(line, 16).unwrap_or(0)));
+
+  // Ties go to the earliest line.
+  let (line, count) = repeats.iter().enumerate().rev().max_by_key(|&(_, count)| count).ok_or("input file is empty")?;
+  println!("line: {}
+repeated blocks: {}", line, count);
+  return Ok(());
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+  if args.len() < 2 {
+    return Err(USAGE.to_string());
+  }
+
+  let challenge: u32 = args[0].parse().map_err(|_| format!("invalid challenge number '{}'", args[0]))?;
+  let input = fs::read_to_string(&args[1]).map_err(|e| format!("cannot read {}: {}", args[1], e))?;
+
+  match challenge {
+    3 => single_byte_xor(&input),
+    4 => detect_single_byte_xor(&input),
+    5 => repeating_key_xor(&input, args.get(2)),
+    8 => detect_ecb(&input),
+    _ => Err(format!("challenge {} has no attack to run", challenge))
+  }
+}
+
+fn main() {
+  let args = Vec::from_iter(env::args().skip(1));
+  if let Err(e) = run(&args) {
+    eprintln!("{}", e);
+    process::exit(1);
+  }
+}
@@ -0,0 +1,13 @@
+use crate::data::*;
+
+// A stream cipher's keystream generator. Encryption and decryption both XOR the next bytes of the keystream into the
+// data, which apply_keystream does for any implementation.
+pub trait Keystream {
+  fn next_bytes(&mut self, n: usize) -> Vec<u8>;
+}
+
+// XORs data with the next data.len() bytes of ks, advancing it by that much.
+#[allow(dead_code)]
+pub fn apply_keystream(data: &ASCIIData, ks: &mut impl Keystream) -> ASCIIData {
+  data ^ &ASCIIData::from(ks.next_bytes(data.len()))
+}
@@ -0,0 +1,3 @@
+pub mod keystream;
+pub mod otp;
+pub mod rc4;
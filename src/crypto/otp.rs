@@ -0,0 +1,69 @@
+use std::fmt::Display;
+
+use crate::data::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OtpError {
+  PadTooShort { pad_len: usize, message_len: usize }
+}
+
+impl Display for OtpError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      OtpError::PadTooShort { pad_len, message_len } => {
+        write!(f, "pad of length {} cannot cover a message of length {}", pad_len, message_len)
+      }
+    }
+  }
+}
+
+// A one-time pad: the message XORed with as many pad bytes as it is long. Unlike repeating-key XOR the pad is never
+// cycled, so a pad shorter than the message is an error rather than being reused. Reusing a pad across messages is
+// still possible (and what attack::crib_drag breaks); nothing here can prevent it.
+pub struct OneTimePad;
+
+impl OneTimePad {
+  #[allow(dead_code)]
+  pub fn encrypt(message: &ASCIIData, pad: &ASCIIData) -> Result<ASCIIData, OtpError> {
+    if pad.len() < message.len() {
+      return Err(OtpError::PadTooShort { pad_len: pad.len(), message_len: message.len() });
+    }
+
+    return Ok(message ^ &pad.truncate(message.len()));
+  }
+
+  // The same operation as encrypt, named for readability at call sites.
+  #[allow(dead_code)]
+  pub fn decrypt(ciphertext: &ASCIIData, pad: &ASCIIData) -> Result<ASCIIData, OtpError> {
+    OneTimePad::encrypt(ciphertext, pad)
+  }
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_one_time_pad() -> Result<(), String> {
+    let message = ASCIIData::from("attack at dawn");
+    let pad = ASCIIData::from_hex("6c73d5240a948c86981bc294814d25e14ec0");
+
+    let ciphertext = OneTimePad::encrypt(&message, &pad).map_err(|e| e.to_string())?;
+    if ciphertext.to_hex_string() != "0d07a14569fface7ec3ba6f5f623" {
+      return Err(format!("wrong ciphertext: {}", ciphertext.to_hex_string()));
+    }
+
+    let decrypted = OneTimePad::decrypt(&ciphertext, &pad).map_err(|e| e.to_string())?;
+    if decrypted != message {
+      return Err(format!("round trip gave {}", decrypted));
+    }
+
+    match OneTimePad::encrypt(&message, &pad.truncate(13)) {
+      Err(OtpError::PadTooShort { pad_len: 13, message_len: 14 }) => {},
+      Err(e) => return Err(format!("wrong error for short pad: {}", e)),
+      Ok(_) => return Err("short pad was accepted".to_string())
+    }
+
+    return Ok(());
+  }
+}
@@ -0,0 +1,92 @@
+use crate::crypto::keystream::*;
+use crate::data::*;
+
+// RC4 keystream generator. Each call to next_byte advances the PRGA by one step.
+pub struct Rc4 {
+  state: [u8; 256],
+  i: u8,
+  j: u8
+}
+
+impl Rc4 {
+  // Key scheduling (KSA). Panics on an empty key or one longer than 256 bytes.
+  pub fn new(key: &[u8]) -> Rc4 {
+    if key.is_empty() || key.len() > 256 {
+      panic!("RC4 keys must be between 1 and 256 bytes (got {})", key.len());
+    }
+
+    let mut state = [0u8; 256];
+    for (i, s) in state.iter_mut().enumerate() {
+      *s = i as u8;
+    }
+
+    let mut j = 0u8;
+    for i in 0..256 {
+      j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+      state.swap(i, j as usize);
+    }
+
+    return Rc4 { state, i: 0, j: 0 };
+  }
+
+  pub fn next_byte(&mut self) -> u8 {
+    self.i = self.i.wrapping_add(1);
+    self.j = self.j.wrapping_add(self.state[self.i as usize]);
+    self.state.swap(self.i as usize, self.j as usize);
+
+    return self.state[self.state[self.i as usize].wrapping_add(self.state[self.j as usize]) as usize];
+  }
+
+  pub fn keystream(&mut self, len: usize) -> ASCIIData { ASCIIData::from(self.next_bytes(len)) }
+}
+
+impl Keystream for Rc4 {
+  fn next_bytes(&mut self, n: usize) -> Vec<u8> { Vec::from_iter((0..n).map(|_| self.next_byte())) }
+}
+
+// Encryption and decryption are the same operation.
+#[allow(dead_code)]
+pub fn rc4(data: &ASCIIData, key: &ASCIIData) -> ASCIIData { apply_keystream(data, &mut Rc4::new(key.bytes())) }
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  // Test vectors from RFC 6229 and the original "Key"/"Plaintext" example.
+  #[test]
+  fn test_rc4() -> Result<(), String> {
+    let res = rc4(&ASCIIData::from("Plaintext"), &ASCIIData::from("Key")).to_hex_string();
+    if res != "bbf316e8d940af0ad3" {
+      return Err(format!("wrong output: {}", res));
+    }
+
+    let res = Rc4::new(&[1, 2, 3, 4, 5]).keystream(16).to_hex_string();
+    if res != "b2396305f03dc027ccc3524a0a1118a8" {
+      return Err(format!("wrong keystream: {}", res));
+    }
+
+    let message = ASCIIData::from("Attack at dawn");
+    let key = ASCIIData::from("Secret");
+    if rc4(&rc4(&message, &key), &key) != message {
+      return Err("decryption does not round trip".to_string());
+    }
+
+    return Ok(());
+  }
+
+  // Applying the keystream in pieces must continue where the previous piece left off, matching one-shot encryption.
+  #[test]
+  fn test_rc4_keystream_trait() -> Result<(), String> {
+    let message = ASCIIData::from("Burning 'em, if you ain't quick and nimble");
+    let key = ASCIIData::from("Secret");
+
+    let mut cipher = Rc4::new(key.bytes());
+    let (head, tail) = message.split_at(17);
+    let res = &apply_keystream(&head, &mut cipher) + &apply_keystream(&tail, &mut cipher);
+    if res != rc4(&message, &key) {
+      return Err(format!("piecewise output {} differs from one-shot", res.to_hex_string()));
+    }
+
+    return Ok(());
+  }
+}
@@ -1,8 +1,36 @@
 use hex;
 use std::fmt::Display;
-use std::ops::BitXor;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::ops::{Add, BitXor, Deref, Index, Range};
 use std::str;
 
+#[cfg(feature = "rand")]
+use rand::{Rng, RngCore};
+
+//-------------------
+//   Decode Errors
+//-------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+  InvalidChar { byte: u8, index: usize },
+  BadLength { len: usize },
+  BadPadding
+}
+
+impl Display for DecodeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      DecodeError::InvalidChar { byte, index } => write!(f, "invalid character 0x{:02x} at index {}", byte, index),
+      DecodeError::BadLength { len } => write!(f, "invalid length {}", len),
+      DecodeError::BadPadding => write!(f, "invalid padding")
+    }
+  }
+}
+
+impl std::error::Error for DecodeError {}
+
 //-------------------------------
 //   Byte Representation Trait
 //-------------------------------
@@ -12,13 +40,49 @@ pub trait ByteRepresentation: Default + Clone {
 
   fn ascii_to_byte(&self, ascii_code: u8) -> u8;
 
-  fn bytes_to_ascii(&self, bytes: &Vec<u8>) -> Vec<u8> {
-    Vec::from_iter(bytes.into_iter().map(|x| self.byte_to_ascii(*x)))
+  fn bytes_to_ascii(&self, bytes: &[u8]) -> Vec<u8> { Vec::from_iter(bytes.iter().map(|x| self.byte_to_ascii(*x))) }
+
+  fn ascii_to_bytes(&self, ascii_codes: &[u8]) -> Vec<u8> {
+    Vec::from_iter(ascii_codes.iter().map(|x| self.ascii_to_byte(*x)))
+  }
+
+  // Like ascii_to_byte, but returns None for ASCII codes outside the alphabet. By default every code is accepted.
+  fn try_ascii_to_byte(&self, ascii_code: u8) -> Option<u8> { Some(self.ascii_to_byte(ascii_code)) }
+
+  // The raw bytes a run of symbol values stands for. Trailing bits that don't make up a whole byte are dropped. By
+  // default the symbols are taken to be the bytes themselves, as for ASCII; encodings override it.
+  fn decode_symbols(&self, symbols: &[u8]) -> Vec<u8> { symbols.to_vec() }
+
+  fn try_ascii_to_bytes(&self, ascii_codes: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut bytes = Vec::with_capacity(ascii_codes.len());
+
+    for (index, &byte) in ascii_codes.iter().enumerate() {
+      match self.try_ascii_to_byte(byte) {
+        Some(b) => bytes.push(b),
+        None => return Err(DecodeError::InvalidChar { byte, index })
+      }
+    }
+
+    return Ok(bytes);
   }
+}
 
-  fn ascii_to_bytes(&self, ascii_codes: &Vec<u8>) -> Vec<u8> {
-    Vec::from_iter(ascii_codes.into_iter().map(|x| self.ascii_to_byte(*x)))
+// Marks ASCII codes outside an alphabet in a reverse lookup table.
+const INVALID_SYMBOL: u8 = 0xff;
+
+// Maps each ASCII code to its index in the alphabet, or INVALID_SYMBOL. A character appearing twice would make decoding
+// ambiguous, so the second occurrence is reported as an invalid character.
+fn reverse_lookup(alphabet: &[u8]) -> Result<[u8; 256], DecodeError> {
+  let mut table = [INVALID_SYMBOL; 256];
+  for (index, &byte) in alphabet.iter().enumerate() {
+    if table[byte as usize] != INVALID_SYMBOL {
+      return Err(DecodeError::InvalidChar { byte, index });
+    }
+
+    table[byte as usize] = index as u8;
   }
+
+  return Ok(table);
 }
 
 //---------------------------------
@@ -27,22 +91,43 @@ pub trait ByteRepresentation: Default + Clone {
 
 #[derive(Clone)]
 pub struct StandardBase16 {
-  ascii_lookup: Vec<u8>
+  ascii_lookup: Vec<u8>,
+  byte_lookup: [u8; 256]
 }
 
+impl StandardBase16 {
+  pub fn with_alphabet(alphabet: [u8; 16]) -> Result<Self, DecodeError> {
+    Ok(Self { ascii_lookup: alphabet.to_vec(), byte_lookup: reverse_lookup(&alphabet)? })
+  }
+}
+
+// The standard alphabet encodes in lowercase but also decodes uppercase digits.
 impl Default for StandardBase16 {
-  fn default() -> Self { Self { ascii_lookup: Vec::from("0123456789abcdef") } }
+  fn default() -> Self {
+    let mut rep = Self::with_alphabet(*b"0123456789abcdef").unwrap();
+    for (i, &upper) in b"ABCDEF".iter().enumerate() {
+      rep.byte_lookup[upper as usize] = 10 + i as u8;
+    }
+
+    return rep;
+  }
 }
 
+// Codes outside the alphabet decode to 0 through the infallible methods; use try_ascii_to_byte to catch them.
 impl ByteRepresentation for StandardBase16 {
   fn byte_to_ascii(&self, byte: u8) -> u8 { self.ascii_lookup[byte as usize] }
 
   fn ascii_to_byte(&self, ascii_code: u8) -> u8 {
-    if 48 <= ascii_code && ascii_code <= 57 {
-      ascii_code - 48
-    } else {
-      10 + (ascii_code - 97)
-    }
+    let symbol = self.byte_lookup[ascii_code as usize];
+    return if symbol == INVALID_SYMBOL { 0 } else { symbol };
+  }
+
+  fn try_ascii_to_byte(&self, ascii_code: u8) -> Option<u8> {
+    Some(self.byte_lookup[ascii_code as usize]).filter(|&symbol| symbol != INVALID_SYMBOL)
+  }
+
+  fn decode_symbols(&self, symbols: &[u8]) -> Vec<u8> {
+    Vec::from_iter(symbols.chunks_exact(2).map(|pair| (pair[0] << 4) | pair[1]))
   }
 }
 
@@ -52,30 +137,74 @@ impl ByteRepresentation for StandardBase16 {
 
 #[derive(Clone)]
 pub struct StandardBase64 {
-  ascii_lookup: Vec<u8>
+  ascii_lookup: Vec<u8>,
+  byte_lookup: [u8; 256]
+}
+
+impl StandardBase64 {
+  pub fn with_alphabet(alphabet: [u8; 64]) -> Result<Self, DecodeError> {
+    Ok(Self { ascii_lookup: alphabet.to_vec(), byte_lookup: reverse_lookup(&alphabet)? })
+  }
 }
 
 impl Default for StandardBase64 {
   fn default() -> Self {
-    Self { ascii_lookup: Vec::from("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/") }
+    Self::with_alphabet(*b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/").unwrap()
   }
 }
 
+// Codes outside the alphabet decode to 0 through the infallible methods; use try_ascii_to_byte to catch them.
 impl ByteRepresentation for StandardBase64 {
   fn byte_to_ascii(&self, byte: u8) -> u8 { self.ascii_lookup[byte as usize] }
 
   fn ascii_to_byte(&self, ascii_code: u8) -> u8 {
-    if 65 <= ascii_code && ascii_code <= 90 {
-      ascii_code - 65
-    } else if 97 <= ascii_code && ascii_code <= 122 {
-      26 + (ascii_code - 97)
-    } else if 48 <= ascii_code && ascii_code <= 57 {
-      52 + (ascii_code - 48)
-    } else if ascii_code == 43 {
-      62
-    } else {
-      63
-    }
+    let symbol = self.byte_lookup[ascii_code as usize];
+    return if symbol == INVALID_SYMBOL { 0 } else { symbol };
+  }
+
+  fn try_ascii_to_byte(&self, ascii_code: u8) -> Option<u8> {
+    Some(self.byte_lookup[ascii_code as usize]).filter(|&symbol| symbol != INVALID_SYMBOL)
+  }
+
+  fn decode_symbols(&self, symbols: &[u8]) -> Vec<u8> {
+    ASCIIData::from_b64_data(&B64Data::from(symbols.to_vec())).to_bytes()
+  }
+}
+
+//---------------------------------
+//   Base 32 Byte Representation
+//---------------------------------
+
+// The RFC 4648 alphabet. Each byte is a 5-bit symbol, except that 32 stands for the '=' padding character.
+#[derive(Clone)]
+pub struct StandardBase32 {
+  ascii_lookup: Vec<u8>,
+  byte_lookup: [u8; 256]
+}
+
+impl Default for StandardBase32 {
+  fn default() -> Self {
+    let alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567=";
+    Self { ascii_lookup: alphabet.to_vec(), byte_lookup: reverse_lookup(alphabet).unwrap() }
+  }
+}
+
+// Codes outside the alphabet decode to 0 through the infallible methods, as for base64, rather than to the padding
+// symbol, which would cut decoding short; use try_ascii_to_byte to catch them.
+impl ByteRepresentation for StandardBase32 {
+  fn byte_to_ascii(&self, byte: u8) -> u8 { self.ascii_lookup[byte as usize] }
+
+  fn ascii_to_byte(&self, ascii_code: u8) -> u8 {
+    let symbol = self.byte_lookup[ascii_code as usize];
+    return if symbol == INVALID_SYMBOL { 0 } else { symbol };
+  }
+
+  fn try_ascii_to_byte(&self, ascii_code: u8) -> Option<u8> {
+    Some(self.byte_lookup[ascii_code as usize]).filter(|&symbol| symbol != INVALID_SYMBOL)
+  }
+
+  fn decode_symbols(&self, symbols: &[u8]) -> Vec<u8> {
+    ASCIIData::from_base32_data(&Base32Data::from(symbols.to_vec())).to_bytes()
   }
 }
 
@@ -105,7 +234,140 @@ pub struct Data<B: ByteRepresentation> {
 impl<B: ByteRepresentation> Data<B> {
   pub fn len(&self) -> usize { self.bytes.len() }
 
+  pub fn is_empty(&self) -> bool { self.bytes.is_empty() }
+
+  // Bounds-checked access to a single byte; None past the end rather than a panic.
+  pub fn get(&self, index: usize) -> Option<u8> { self.bytes.get(index).copied() }
+
   pub fn bytes(&self) -> &Vec<u8> { &self.bytes }
+
+  // How many times each byte value occurs, indexed by value.
+  pub fn byte_histogram(&self) -> [u64; 256] {
+    let mut counts = [0u64; 256];
+    for &b in &self.bytes {
+      counts[b as usize] += 1
+    }
+
+    return counts;
+  }
+
+  pub fn popcount(&self) -> u64 { self.bytes.iter().map(|b| b.count_ones() as u64).sum() }
+
+  // The fraction of set bits, from 0.0 to 1.0. Empty data has a weight of 0.0.
+  pub fn hamming_weight_normalized(&self) -> f32 {
+    if self.bytes.is_empty() {
+      return 0.0;
+    }
+
+    return self.popcount() as f32 / (8 * self.len()) as f32;
+  }
+
+  pub fn slice(&self, range: Range<usize>) -> Data<B> {
+    if range.start > range.end || range.end > self.len() {
+      panic!("Cannot slice {}..{} out of data of length {}", range.start, range.end, self.len());
+    }
+
+    return Data { bytes: self.bytes[range].to_vec(), base_rep: self.base_rep.clone() };
+  }
+
+  // The first len bytes. Panics if there are fewer than len.
+  pub fn truncate(&self, len: usize) -> Data<B> {
+    if len > self.len() {
+      panic!("Cannot truncate data of length {} to length {}", self.len(), len);
+    }
+
+    return self.slice(0..len);
+  }
+
+  // The bytes before and from mid, e.g. to split off an IV. Shadows [u8]::split_at, returning Data rather than slices.
+  // Panics if mid is past the end.
+  pub fn split_at(&self, mid: usize) -> (Data<B>, Data<B>) {
+    if mid > self.len() {
+      panic!("Cannot split data of length {} at {}", self.len(), mid);
+    }
+
+    return (self.slice(0..mid), self.slice(mid..self.len()));
+  }
+
+  // Right-pads with fill up to exactly len bytes, e.g. zero- or space-padding to a block boundary. This is not cipher
+  // padding: nothing records how much was added. None if the data is already longer than len.
+  pub fn pad_to(&self, len: usize, fill: u8) -> Option<Data<B>> {
+    if len < self.len() {
+      return None;
+    }
+
+    let mut bytes = self.bytes.clone();
+    bytes.resize(len, fill);
+    return Some(Data::from(bytes));
+  }
+
+  pub fn repeated(byte: u8, len: usize) -> Data<B> { Data::from(vec![byte; len]) }
+
+  pub fn xor_with_byte(&self, byte: u8) -> Data<B> { Data::from_iter(self.bytes.iter().map(|b| b ^ byte)) }
+
+  // The pattern repeated (and truncated) to exactly len bytes. Panics on an empty pattern unless len is 0.
+  pub fn cycled(pattern: &Data<B>, len: usize) -> Data<B> {
+    if pattern.is_empty() && len > 0 {
+      panic!("Cannot cycle an empty pattern to length {}", len);
+    }
+
+    return Data::from_iter(pattern.bytes.iter().copied().cycle().take(len));
+  }
+
+  pub fn extend(&mut self, other: &Data<B>) { self.bytes.extend_from_slice(&other.bytes) }
+
+  // A copy of the bytes, for when Vec::from would give up the data. As with bytes(), these are symbol values for the
+  // encoded representations (one nibble per byte for HexData, and so on).
+  pub fn to_bytes(&self) -> Vec<u8> { self.bytes.clone() }
+
+  // Standard padded base64 of the raw bytes the data stands for: the bytes themselves for ASCIIData, and what the
+  // symbols decode to for the encoded representations (so HexData "49276d" gives "SSdt"). The counterpart to
+  // ASCIIData::from_base64.
+  pub fn to_base64_string(&self) -> String {
+    let raw = ASCIIData::from(self.base_rep.decode_symbols(&self.bytes));
+    let mut encoded = B64Data::from(&raw).to_string();
+    encoded.push_str(["", "==", "="][raw.len() % 3]);
+    return encoded;
+  }
+
+  // The strict counterpart to to_string, which fails rather than substituting replacement characters for invalid UTF-8.
+  pub fn try_to_string(&self) -> Result<String, str::Utf8Error> {
+    Ok(str::from_utf8(&self.base_rep.bytes_to_ascii(&self.bytes))?.to_string())
+  }
+
+  // The validating counterpart to From<&str>: fails on the first ASCII code outside the representation's alphabet.
+  pub fn try_from_ascii<T: AsRef<[u8]>>(value: T) -> Result<Data<B>, DecodeError> {
+    Ok(Self::from(B::default().try_ascii_to_bytes(value.as_ref())?))
+  }
+
+  // Reads the reader to the end; the bytes are taken as-is, as with From<Vec<u8>>.
+  pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Data<B>> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    return Ok(Data::from(bytes));
+  }
+}
+
+// Equality and hashing only look at the stored symbol values and deliberately ignore the representation's alphabet:
+// two HexData holding the same nibbles are equal even if one prints them with a custom alphabet.
+impl<B: ByteRepresentation> PartialEq for Data<B> {
+  fn eq(&self, other: &Self) -> bool { self.bytes == other.bytes }
+}
+
+impl<B: ByteRepresentation> Eq for Data<B> {}
+
+impl<B: ByteRepresentation> Hash for Data<B> {
+  fn hash<H: Hasher>(&self, state: &mut H) { self.bytes.hash(state) }
+}
+
+impl<B: ByteRepresentation> AsRef<[u8]> for Data<B> {
+  fn as_ref(&self) -> &[u8] { &self.bytes }
+}
+
+impl<B: ByteRepresentation> Deref for Data<B> {
+  type Target = [u8];
+
+  fn deref(&self) -> &Self::Target { &self.bytes }
 }
 
 impl<B: ByteRepresentation> IntoIterator for Data<B> {
@@ -127,6 +389,14 @@ impl<B: ByteRepresentation> From<Vec<u8>> for Data<B> {
   fn from(value: Vec<u8>) -> Self { Self { bytes: value, base_rep: B::default() } }
 }
 
+impl<B: ByteRepresentation> From<&[u8]> for Data<B> {
+  fn from(value: &[u8]) -> Self { Self::from(value.to_vec()) }
+}
+
+impl<B: ByteRepresentation, const N: usize> From<[u8; N]> for Data<B> {
+  fn from(value: [u8; N]) -> Self { Self::from(value.to_vec()) }
+}
+
 impl<B: ByteRepresentation> From<String> for Data<B> {
   fn from(value: String) -> Self { Self::from(B::default().ascii_to_bytes(&Vec::from(value))) }
 }
@@ -135,6 +405,18 @@ impl<B: ByteRepresentation> From<&str> for Data<B> {
   fn from(value: &str) -> Self { Self::from(value.to_string()) }
 }
 
+impl<B: ByteRepresentation> Index<usize> for Data<B> {
+  type Output = u8;
+
+  fn index(&self, index: usize) -> &Self::Output { &self.bytes[index] }
+}
+
+impl<B: ByteRepresentation> Index<Range<usize>> for Data<B> {
+  type Output = [u8];
+
+  fn index(&self, index: Range<usize>) -> &Self::Output { &self.bytes[index] }
+}
+
 impl<B: ByteRepresentation> BitXor<&Data<B>> for &Data<B> {
   type Output = Data<B>;
 
@@ -146,49 +428,196 @@ impl<B: ByteRepresentation> BitXor<&Data<B>> for &Data<B> {
       panic!("Cannot XOR sequences of different lengths ({} and {})", n1, n2);
     }
 
-    let mut res = Vec::with_capacity(n1);
+    // XOR eight bytes at a time, then finish the last n1 % 8 bytes one by one.
+    let mut res = vec![0u8; n1];
+    let mut res_words = res.chunks_exact_mut(8);
+    let lhs_words = self.bytes.chunks_exact(8);
+    let rhs_words = rhs.bytes.chunks_exact(8);
+    let tail = lhs_words.remainder().iter().zip(rhs_words.remainder());
+
+    for ((out, a), b) in res_words.by_ref().zip(lhs_words).zip(rhs_words) {
+      let word = u64::from_ne_bytes(a.try_into().unwrap()) ^ u64::from_ne_bytes(b.try_into().unwrap());
+      out.copy_from_slice(&word.to_ne_bytes());
+    }
 
-    for i in 0..n1 {
-      res.push(self.bytes[i] ^ rhs.bytes[i]);
+    for (out, (a, b)) in res_words.into_remainder().iter_mut().zip(tail) {
+      *out = a ^ b;
     }
 
     return Data::from(res);
   }
 }
 
+// XORs data against key repeated (and truncated) to the same length, as in repeating-key XOR or applying a keystream.
+pub fn xor_cycled<B: ByteRepresentation>(data: &Data<B>, key: &Data<B>) -> Data<B> {
+  if key.is_empty() {
+    panic!("Cannot XOR against an empty key");
+  }
+
+  return Data::from_iter(data.bytes.iter().zip(key.bytes.iter().cycle()).map(|(x, k)| x ^ k));
+}
+
+// XORs the overlapping prefix of a and b and copies the rest of the longer one unchanged, so it never panics on a
+// length mismatch such as a short final block.
+pub fn xor_truncated<B: ByteRepresentation>(a: &Data<B>, b: &Data<B>) -> Data<B> {
+  let (longer, shorter) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+  let mut res = longer.clone();
+  for (x, y) in res.bytes.iter_mut().zip(&shorter.bytes) {
+    *x ^= y;
+  }
+
+  return res;
+}
+
 impl<B: ByteRepresentation> BitXor<Data<B>> for Data<B> {
   type Output = Data<B>;
 
   fn bitxor(self, rhs: Data<B>) -> Self::Output { &self ^ &rhs }
 }
 
+// Shorthand for xor_with_byte, e.g. &ciphertext ^ key in single-byte XOR attacks.
+impl<B: ByteRepresentation> BitXor<u8> for &Data<B> {
+  type Output = Data<B>;
+
+  fn bitxor(self, rhs: u8) -> Self::Output { self.xor_with_byte(rhs) }
+}
+
+impl<B: ByteRepresentation> Add<&Data<B>> for &Data<B> {
+  type Output = Data<B>;
+
+  fn add(self, rhs: &Data<B>) -> Self::Output {
+    let mut res = self.clone();
+    res.extend(rhs);
+    return res;
+  }
+}
+
+// Writing appends to the data, so it can be used as a sink for write! or io::copy. Written bytes are taken as-is, i.e.
+// as symbols of the representation rather than ASCII text to be decoded.
+impl<B: ByteRepresentation> Write for Data<B> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.bytes.extend_from_slice(buf);
+    return Ok(buf.len());
+  }
+
+  fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
 //-----------------------
 //   ASCII Data Struct
 //-----------------------
 
 pub type ASCIIData = Data<StandardASCII>;
 
+// Every byte is valid ASCIIData, so this only fails for consistency with the other representations' parsers.
+impl str::FromStr for ASCIIData {
+  type Err = DecodeError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> { ASCIIData::try_from_ascii(s) }
+}
+
 impl ASCIIData {
   pub fn into<B: ByteRepresentation>(&self) -> Data<B> { Data::from(B::default().ascii_to_bytes(&self.bytes)) }
 
   // This is not the same as directly converting hexadecimal into ASCII; this is a special ASCII encoding in hex.
-  pub fn from_hex_data(value: HexData) -> ASCIIData { ASCIIData::from(hex::decode(value.bytes).unwrap()) }
+  pub fn from_hex_data(value: HexData) -> ASCIIData {
+    ASCIIData::from(hex::decode(value.base_rep.bytes_to_ascii(&value.bytes)).unwrap())
+  }
 
   // Same goes for this one; this is not equivalent to From<String>() for ASCIIData.
   pub fn from_hex<T: AsRef<[u8]>>(value: T) -> ASCIIData { ASCIIData::from(hex::decode(value).unwrap()) }
 
   // Same goes for this one; this is not equivalent to to_string().
-  pub fn to_hex_string(self) -> String { hex::encode(self.bytes) }
+  pub fn to_hex_string(&self) -> String { hex::encode(&self.bytes) }
+}
+
+// Random keys, IVs and prefixes are raw bytes, so random generation lives on ASCIIData only; uniformly random bytes are
+// not valid digits in the other representations. The _with variants draw from a caller-supplied generator, so tests
+// can pass a seeded one and get the same bytes on every run.
+#[cfg(feature = "rand")]
+impl ASCIIData {
+  pub fn random(len: usize) -> ASCIIData { ASCIIData::random_with(&mut rand::rng(), len) }
+
+  pub fn random_in(len_range: Range<usize>) -> ASCIIData { ASCIIData::random_in_with(&mut rand::rng(), len_range) }
+
+  pub fn random_with(rng: &mut impl RngCore, len: usize) -> ASCIIData {
+    let mut bytes = vec![0u8; len];
+    rng.fill_bytes(&mut bytes);
+    return ASCIIData::from(bytes);
+  }
+
+  pub fn random_in_with(rng: &mut impl RngCore, len_range: Range<usize>) -> ASCIIData {
+    let len = rng.random_range(len_range);
+    return ASCIIData::random_with(rng, len);
+  }
 }
 
 impl<B: ByteRepresentation> From<&Data<B>> for ASCIIData {
   fn from(value: &Data<B>) -> Self { Self::from(value.base_rep.bytes_to_ascii(&value.bytes)) }
 }
 
+// Invalid UTF-8 (e.g. raw ciphertext in an ASCIIData) is shown with replacement characters; use try_to_string to reject
+// it instead. With {:#}, the underlying bytes are printed as an xxd-style hex dump: an offset, sixteen bytes in groups
+// of two, and an ASCII gutter with '.' standing in for unprintable bytes.
 impl<B: ByteRepresentation> Display for Data<B> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if f.alternate() {
+      for (i, line) in self.bytes.chunks(16).enumerate() {
+        if i > 0 {
+          writeln!(f)?;
+        }
+
+        let mut hex = String::with_capacity(39);
+        for (j, byte) in line.iter().enumerate() {
+          if j > 0 && j % 2 == 0 {
+            hex.push(' ');
+          }
+
+          hex.push_str(&format!("{:02x}", byte));
+        }
+
+        let gutter =
+          String::from_iter(line.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }));
+        write!(f, "{:08x}: {:<39}  {}", 16 * i, hex, gutter)?;
+      }
+
+      return Ok(());
+    }
+
     let ascii_codes = self.base_rep.bytes_to_ascii(&self.bytes);
-    return write!(f, "{}", str::from_utf8(&ascii_codes.as_slice()).unwrap());
+    return write!(f, "{}", String::from_utf8_lossy(&ascii_codes));
+  }
+}
+
+// The underlying bytes in hex, one line per block_size bytes (the last line may be short), so repeated blocks line up
+// when eyeballing ECB or CBC output. Panics on a block size of 0.
+pub fn format_blocks<B: ByteRepresentation>(data: &Data<B>, block_size: usize) -> String {
+  if block_size == 0 {
+    panic!("Cannot split data into blocks of size 0");
+  }
+
+  return Vec::from_iter(data.bytes.chunks(block_size).map(hex::encode)).join("\n");
+}
+
+// With the serde feature, Data is (de)serialized as its Display string: hex digits for HexData, base64 for B64Data and
+// so on. ASCIIData holding bytes that aren't valid UTF-8 can't be written this way and fails to serialize rather than
+// being mangled. Deserializing goes through FromStr, so it accepts what any standard encoder writes (padded base64,
+// say) and rejects what FromStr rejects, such as odd-length hex.
+#[cfg(feature = "serde")]
+impl<B: ByteRepresentation> serde::Serialize for Data<B> {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.try_to_string().map_err(serde::ser::Error::custom)?)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, B: ByteRepresentation> serde::Deserialize<'de> for Data<B>
+where
+  Data<B>: str::FromStr<Err = DecodeError>
+{
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    return text.parse().map_err(serde::de::Error::custom);
   }
 }
 
@@ -198,37 +627,223 @@ impl<B: ByteRepresentation> Display for Data<B> {
 
 pub type HexData = Data<StandardBase16>;
 
+// The validating way to parse user-supplied hex, e.g. "1c0111".parse::<HexData>(): unlike From<&str>, it rejects
+// characters that aren't hex digits (either case) and strings of odd length, which can't describe whole bytes.
+impl str::FromStr for HexData {
+  type Err = DecodeError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if !s.len().is_multiple_of(2) {
+      return Err(DecodeError::BadLength { len: s.len() });
+    }
+
+    return HexData::try_from_ascii(s);
+  }
+}
+
 //-------------------------
 //   Base 64 Data Struct
 //-------------------------
 
 pub type B64Data = Data<StandardBase64>;
 
+// The validating way to parse user-supplied base64, e.g. "SSdt".parse::<B64Data>(). Up to two '=' padding characters
+// may end the string, but only to fill out the last group of four (BadPadding otherwise); they are dropped, since
+// B64Data has no padding symbol. Unpadded input is accepted too, except with a length one more than a multiple of four,
+// which can't describe whole bytes. Any other character outside the alphabet is an error.
+impl str::FromStr for B64Data {
+  type Err = DecodeError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let unpadded = s.strip_suffix("==").or_else(|| s.strip_suffix('=')).unwrap_or(s);
+    if unpadded.len() < s.len() && !s.len().is_multiple_of(4) {
+      return Err(DecodeError::BadPadding);
+    }
+
+    let data = B64Data::try_from_ascii(unpadded)?;
+    if data.len() % 4 == 1 {
+      return Err(DecodeError::BadLength { len: s.len() });
+    }
+
+    return Ok(data);
+  }
+}
+
+// Lazily turns hex digits (nibble values) into base64 symbols, two for every three digits. As in standard base64, the
+// bits run from the front: a short final group is zero-filled on the right and yields only the symbols it needs (one
+// for a single digit, two for two), so whole bytes encode exactly as B64Data::from(&ASCIIData) would.
+pub fn hex_to_b64_iter<I: Iterator<Item = u8>>(mut digits: I) -> impl Iterator<Item = u8> {
+  let mut pending = None;
+
+  return std::iter::from_fn(move || {
+    if let Some(symbol) = pending.take() {
+      return Some(symbol);
+    }
+
+    let mut acc = 0u16;
+    let mut group_len = 0;
+    for digit in digits.by_ref().take(3) {
+      acc |= (digit as u16) << (8 - 4 * group_len);
+      group_len += 1;
+    }
+
+    if group_len == 0 {
+      return None;
+    }
+
+    if group_len >= 2 {
+      pending = Some((acc % 64) as u8);
+    }
+
+    return Some((acc / 64) as u8);
+  });
+}
+
 impl From<&HexData> for B64Data {
   // Challenge 1, Set 1
-  fn from(value: &HexData) -> Self {
-    let n = value.len();
-    let mut b64_bytes: Vec<u8> = Vec::with_capacity(2 * n / 3);
+  fn from(value: &HexData) -> Self { B64Data::from_iter(hex_to_b64_iter(value.bytes().iter().copied())) }
+}
+
+// Packs a group of up to 3 bytes into base64 symbols, most significant bits first. A partial group is zero-filled on
+// the right and yields only the symbols it needs (2 for one byte, 3 for two), since B64Data has no padding character.
+fn pack_b64_group(group: &[u8], symbols: &mut Vec<u8>) {
+  let mut acc = 0u32;
+  for (i, &byte) in group.iter().enumerate() {
+    acc |= (byte as u32) << (16 - 8 * i);
+  }
+
+  for i in 0..(8 * group.len()).div_ceil(6) {
+    symbols.push(((acc >> (18 - 6 * i)) & 63) as u8);
+  }
+}
+
+impl From<&ASCIIData> for B64Data {
+  fn from(value: &ASCIIData) -> Self {
+    let mut symbols = Vec::with_capacity((4 * value.len()).div_ceil(3));
+    for group in value.bytes().chunks(3) {
+      pack_b64_group(group, &mut symbols);
+    }
+
+    return B64Data::from(symbols);
+  }
+}
+
+impl ASCIIData {
+  // The inverse of B64Data::from(&ASCIIData). Trailing bits that don't make up a whole byte are dropped.
+  pub fn from_b64_data(value: &B64Data) -> ASCIIData {
+    let mut bytes = Vec::with_capacity(3 * value.len() / 4);
+    let mut acc = 0u16;
+    let mut bits = 0;
+
+    for &symbol in value.bytes() {
+      acc = (acc << 6) | symbol as u16;
+      bits += 6;
+
+      if bits >= 8 {
+        bits -= 8;
+        bytes.push((acc >> bits) as u8);
+        acc &= (1 << bits) - 1;
+      }
+    }
+
+    return ASCIIData::from(bytes);
+  }
+
+  // Decodes a standard base64 string straight into bytes, validating it as B64Data's FromStr does.
+  pub fn from_base64<T: AsRef<str>>(s: T) -> Result<ASCIIData, DecodeError> {
+    Ok(ASCIIData::from_b64_data(&s.as_ref().parse::<B64Data>()?))
+  }
+
+  // Decodes base64 wrapped across lines, as in the challenge files: all whitespace (including CR and the final newline)
+  // is skipped and the rest is validated as by from_base64, padding included.
+  pub fn from_b64_multiline(text: &str) -> Result<ASCIIData, DecodeError> {
+    ASCIIData::from_base64(String::from_iter(text.chars().filter(|c| !c.is_ascii_whitespace())))
+  }
+}
+
+// Streams base64 text for everything read from reader into writer, matching B64Data::from(&ASCIIData) byte for byte
+// without holding the whole input in memory.
+pub fn encode_b64_stream<R: Read, W: Write>(mut reader: R, mut writer: W) -> io::Result<()> {
+  let rep = StandardBase64::default();
+  let mut buffer = [0u8; 3 * 1024];
+  let mut filled = 0;
+  let mut symbols = Vec::with_capacity(4 * 1024);
+
+  loop {
+    let read = reader.read(&mut buffer[filled..])?;
+    filled += read;
+
+    // Only whole groups can be packed until the reader is exhausted; hold any leftover bytes back for the next read.
+    let whole = if read == 0 { filled } else { filled - filled % 3 };
+    for group in buffer[..whole].chunks(3) {
+      pack_b64_group(group, &mut symbols);
+    }
 
-    let mut acc: u16 = 0;
-    let start_count = n % 3;
-    let mut count = if start_count == 0 { 3 } else { start_count };
-    let pows = vec![1, 16, 256];
+    writer.write_all(&rep.bytes_to_ascii(&symbols))?;
+    symbols.clear();
 
-    for bytes in value.bytes() {
-      acc += (*bytes as u16) * pows[count - 1];
-      count -= 1;
+    if read == 0 {
+      return writer.flush();
+    }
+
+    buffer.copy_within(whole..filled, 0);
+    filled -= whole;
+  }
+}
+
+//-------------------------
+//   Base 32 Data Struct
+//-------------------------
+
+pub type Base32Data = Data<StandardBase32>;
+
+// '=' is part of the base32 alphabet, so padding needs no special handling; anything else outside it is an error.
+impl str::FromStr for Base32Data {
+  type Err = DecodeError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> { Base32Data::try_from_ascii(s) }
+}
+
+impl From<&ASCIIData> for Base32Data {
+  // Every 5 bytes become 8 symbols; a final partial group is zero-filled and padded out to 8 symbols with '='.
+  fn from(value: &ASCIIData) -> Self {
+    let mut symbols = Vec::with_capacity(8 * value.len().div_ceil(5));
+
+    for group in value.bytes().chunks(5) {
+      let mut acc = 0u64;
+      for (i, &byte) in group.iter().enumerate() {
+        acc |= (byte as u64) << (32 - 8 * i);
+      }
+
+      let used = (8 * group.len()).div_ceil(5);
+      for i in 0..8 {
+        symbols.push(if i < used { ((acc >> (35 - 5 * i)) & 31) as u8 } else { 32 });
+      }
+    }
 
-      if count == 0 {
-        b64_bytes.push((acc / 64) as u8);
-        b64_bytes.push((acc % 64) as u8);
+    return Base32Data::from(symbols);
+  }
+}
 
-        acc = 0;
-        count = 3;
+impl ASCIIData {
+  // The inverse of Base32Data::from(&ASCIIData). Trailing bits that don't make up a whole byte are dropped.
+  pub fn from_base32_data(value: &Base32Data) -> ASCIIData {
+    let mut bytes = Vec::with_capacity(5 * value.len() / 8);
+    let mut acc = 0u16;
+    let mut bits = 0;
+
+    for &symbol in value.bytes().iter().take_while(|&&symbol| symbol != 32) {
+      acc = (acc << 5) | symbol as u16;
+      bits += 5;
+
+      if bits >= 8 {
+        bits -= 8;
+        bytes.push((acc >> bits) as u8);
+        acc &= (1 << bits) - 1;
       }
     }
 
-    return B64Data::from(b64_bytes);
+    return ASCIIData::from(bytes);
   }
 }
 
@@ -239,6 +854,10 @@ impl From<&HexData> for B64Data {
 #[allow(unused_imports)]
 mod tests {
   use super::*;
+  use std::collections::hash_map::DefaultHasher;
+  use std::collections::HashSet;
+  use std::fs::File;
+  use std::io::BufReader;
 
   #[test]
   fn test_hex_to_b64() -> Result<(), String> {
@@ -250,8 +869,8 @@ mod tests {
 
     let results = vec![
       "SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t",
-      "BJJ20ga2lsbGluZyB5b3VyIGJyYWluIGxpa2UgYSBwb2lzb25vdXMgbXVzaHJvb2",
-      "AEknbSBraWxsaW5nIHlvdXIgYnJhaW4gbGlrZSBhIHBvaXNvbm91cyBtdXNocm9v",
+      "SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29g",
+      "SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb28",
     ];
 
     for i in 0..inputs.len() {
@@ -264,6 +883,828 @@ mod tests {
     return Ok(());
   }
 
+  #[test]
+  fn test_hex_to_b64_iter() -> Result<(), String> {
+    // Reference encoding: concatenate the digits' bits, zero-fill to a multiple of 6 and cut into symbols.
+    fn hex_to_b64_bits(digits: &[u8]) -> Vec<u8> {
+      let mut bits = Vec::from_iter(digits.iter().flat_map(|&digit| (0..4).rev().map(move |i| (digit >> i) & 1)));
+      bits.resize(bits.len().div_ceil(6) * 6, 0);
+      return Vec::from_iter(bits.chunks(6).map(|chunk| chunk.iter().fold(0, |acc, &bit| 2 * acc + bit)));
+    }
+
+    let hex = "49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d";
+    for len in 0..hex.len() {
+      let digits = HexData::from(&hex[..len]);
+      let lazy = Vec::from_iter(hex_to_b64_iter(digits.bytes().iter().copied()));
+      if lazy != hex_to_b64_bits(digits.bytes()) {
+        return Err(format!("iterator disagrees on the first {} digits", len));
+      }
+    }
+
+    return Ok(());
+  }
+
+  // Any whole number of bytes must survive hex -> base64 -> bytes, and match encoding the bytes directly.
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_hex_to_b64_round_trip() -> Result<(), String> {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::seed_from_u64(392);
+    for len in 0..200 {
+      let bytes = ASCIIData::random_with(&mut rng, len);
+      let b64 = B64Data::from(&HexData::from(bytes.to_hex_string()));
+
+      if b64 != B64Data::from(&bytes) {
+        return Err(format!("{} bytes encode to {} via hex", len, b64));
+      }
+
+      if ASCIIData::from_b64_data(&b64) != bytes {
+        return Err(format!("{} bytes do not round trip through {}", len, b64));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_custom_alphabet() -> Result<(), String> {
+    // A URL-safe-style alphabet with the letters rotated, so no symbol keeps its standard character.
+    let alphabet: [u8; 64] = *b"NOPQRSTUVWXYZABCDEFGHIJKLMnopqrstuvwxyzabcdefghijklm0123456789-_";
+    let custom = StandardBase64::with_alphabet(alphabet).map_err(|e| e.to_string())?;
+
+    let message = ASCIIData::from("I'm killing your brain like a poisonous mushroom");
+    let symbols = B64Data::from(&message);
+    let text = custom.bytes_to_ascii(symbols.bytes());
+    if text == symbols.to_string().as_bytes() {
+      return Err("custom alphabet encoded like the standard one".to_string());
+    }
+
+    let decoded = custom.try_ascii_to_bytes(&text).map_err(|e| e.to_string())?;
+    if ASCIIData::from_b64_data(&B64Data::from(decoded)) != message {
+      return Err(format!("round trip through {} failed", String::from_utf8_lossy(&text)));
+    }
+
+    let mut duplicated = alphabet;
+    duplicated[63] = b'N';
+    match StandardBase64::with_alphabet(duplicated) {
+      Err(DecodeError::InvalidChar { byte: b'N', index: 63 }) => {},
+      Err(e) => return Err(format!("wrong error: {}", e)),
+      Ok(_) => return Err("alphabet with a duplicate was accepted".to_string())
+    }
+
+    let hex = StandardBase16::with_alphabet(*b"fedcba9876543210").map_err(|e| e.to_string())?;
+    if hex.ascii_to_bytes(b"f0") != [0, 15] || hex.bytes_to_ascii(&[1, 14]) != b"e1" {
+      return Err("custom base16 alphabet mapped the wrong way".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_b64_invalid_char() -> Result<(), String> {
+    let valid = B64Data::try_from_ascii("SSdtIGtp+/").map_err(|e| e.to_string())?;
+    if valid.to_string() != "SSdtIGtp+/" {
+      return Err(format!("wrong output: {}", valid));
+    }
+
+    match B64Data::try_from_ascii("SSdt#GtpbGxp") {
+      Err(DecodeError::InvalidChar { byte: b'#', index: 4 }) => {},
+      Err(e) => return Err(format!("wrong error: {}", e)),
+      Ok(data) => return Err(format!("invalid input decoded to {}", data))
+    }
+
+    return Ok(());
+  }
+
+  // DecodeError is a std::error::Error, so it can be passed up through ? alongside other errors and still be matched.
+  #[test]
+  fn test_decode_error() -> Result<(), String> {
+    fn parse(s: &str) -> Result<HexData, Box<dyn std::error::Error>> { Ok(s.parse::<HexData>()?) }
+
+    let err = match parse("49276d20kb") {
+      Err(e) => e,
+      Ok(data) => return Err(format!("parsed {}", data))
+    };
+
+    match err.downcast_ref::<DecodeError>() {
+      Some(DecodeError::InvalidChar { byte: b'k', index: 8 }) => {},
+      _ => return Err(format!("wrong error: {}", err))
+    }
+
+    if err.to_string() != "invalid character 0x6b at index 8" {
+      return Err(format!("wrong message: {}", err));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_parse_hex() -> Result<(), String> {
+    let hex = "1c0111001F".parse::<HexData>().map_err(|e| e.to_string())?;
+    if hex != HexData::from("1c0111001f") {
+      return Err(format!("wrong output: {}", hex));
+    }
+
+    match "1c0111001".parse::<HexData>() {
+      Err(DecodeError::BadLength { len: 9 }) => {},
+      res => return Err(format!("odd-length string gave {:?}", res.map(|data| data.to_string())))
+    }
+
+    match "1c01g1001f".parse::<HexData>() {
+      Err(DecodeError::InvalidChar { byte: b'g', index: 4 }) => {},
+      res => return Err(format!("non-hex string gave {:?}", res.map(|data| data.to_string())))
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_index() -> Result<(), String> {
+    let hex = HexData::from("1c0111001f");
+
+    if hex[0] != 1 || hex[1] != 12 || hex[9] != 15 {
+      return Err(format!("wrong bytes: {} {} {}", hex[0], hex[1], hex[9]));
+    }
+
+    if hex[2..6] != [0, 1, 1, 1] {
+      return Err(format!("wrong slice: {:?}", &hex[2..6]));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_slice() -> Result<(), String> {
+    let b64 = B64Data::from("SSdtIGtpbGxpbmcgeW91ciBicmFpbg");
+    let res = b64.slice(4..12).to_string();
+
+    if res != b64.to_string()[4..12] {
+      return Err(format!("wrong output: {}", res));
+    }
+
+    if !b64.slice(3..3).to_string().is_empty() {
+      return Err("empty slice is not empty".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_truncate_and_split_at() -> Result<(), String> {
+    let hex = HexData::from("1c01");
+
+    let (head, tail) = hex.split_at(1);
+    if head.to_string() != "1" || tail.to_string() != "c01" {
+      return Err(format!("split into {} and {}", head, tail));
+    }
+
+    if hex.truncate(1) != head || hex.truncate(4) != hex || !hex.truncate(0).is_empty() {
+      return Err(format!("wrong truncation: {}", hex.truncate(1)));
+    }
+
+    let (all, none) = hex.split_at(4);
+    if all != hex || !none.is_empty() {
+      return Err("splitting at the end should leave an empty tail".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_pad_to() -> Result<(), String> {
+    let data = ASCIIData::from([1, 2, 3]);
+    let res = data.pad_to(8, 0x00).ok_or("padding to a longer length failed")?;
+    if res != ASCIIData::from([1, 2, 3, 0, 0, 0, 0, 0]) {
+      return Err(format!("padded to {}", res.to_hex_string()));
+    }
+
+    if data.pad_to(3, b' ') != Some(data.clone()) {
+      return Err("padding to the current length changed the data".to_string());
+    }
+
+    if ASCIIData::from("YELLOW SUBMARINE").pad_to(8, 0x00).is_some() {
+      return Err("padding to a shorter length succeeded".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  #[should_panic(expected = "Cannot split data of length 4 at 5")]
+  fn test_split_at_out_of_bounds() { HexData::from("1c01").split_at(5); }
+
+  #[test]
+  #[should_panic(expected = "Cannot slice 4..12 out of data of length 8")]
+  fn test_slice_out_of_bounds() { B64Data::from("SSdtIGtp").slice(4..12); }
+
+  #[test]
+  fn test_eq_and_hash() -> Result<(), String> {
+    fn hash_of(data: &HexData) -> u64 {
+      let mut hasher = DefaultHasher::new();
+      data.hash(&mut hasher);
+      return hasher.finish();
+    }
+
+    let hex1 = HexData::from("1c0111001f010100061a024b53535009181c");
+    let hex2 = HexData::from("1c0111001f010100061a024b53535009181c");
+    let hex3 = HexData::from("686974207468652062756c6c277320657965");
+
+    if hex1 != hex2 || hash_of(&hex1) != hash_of(&hex2) {
+      return Err("identical data compares or hashes differently".to_string());
+    }
+
+    if hex1 == hex3 {
+      return Err("different data compares equal".to_string());
+    }
+
+    // Repeated blocks collapse in a set, which is how ECB ciphertexts give themselves away.
+    let blocks = [hex1.slice(0..16), hex3.slice(0..16), hex2.slice(0..16)];
+    let unique = HashSet::<&HexData>::from_iter(blocks.iter());
+    if unique.len() != 2 {
+      return Err(format!("expected 2 unique blocks, got {}", unique.len()));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_from_slice_and_array() -> Result<(), String> {
+    let block: [u8; 16] = *b"YELLOW SUBMARINE";
+
+    let from_slice = ASCIIData::from(&block[..6]);
+    if from_slice.to_string() != "YELLOW" {
+      return Err(format!("wrong output: {}", from_slice));
+    }
+
+    let from_array = ASCIIData::from(block);
+    if from_array.len() != 16 || from_array.to_string() != "YELLOW SUBMARINE" {
+      return Err(format!("wrong output: {}", from_array));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_xor_truncated() -> Result<(), String> {
+    let block = ASCIIData::from("YELLOW SUBMARINE");
+    let tail = ASCIIData::from([0x20u8; 6]);
+    let expected = "yellow SUBMARINE";
+
+    for res in [xor_truncated(&block, &tail), xor_truncated(&tail, &block)] {
+      if res.to_string() != expected {
+        return Err(format!("wrong output: {}", res));
+      }
+    }
+
+    if xor_truncated(&block, &block) != ASCIIData::repeated(0, 16) {
+      return Err("equal-length inputs should XOR like ^".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_format_blocks() -> Result<(), String> {
+    let block = ASCIIData::from("YELLOW SUBMARINE");
+    let data = &(&(&block + &ASCIIData::from("ICE ICE BABY\x04\x04\x04\x04")) + &block) + &ASCIIData::from("!");
+
+    let res = format_blocks(&data, 16);
+    let lines = Vec::from_iter(res.lines());
+    if lines.len() != 4 || lines[0] != lines[2] || lines[0] != "59454c4c4f57205355424d4152494e45" || lines[3] != "21" {
+      return Err(format!("wrong output:\n{}", res));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_xor_with_byte() -> Result<(), String> {
+    let data = ASCIIData::from("Cooking MC's like a pound of bacon");
+    if data.xor_with_byte(0) != data {
+      return Err("XOR with 0 changed the data".to_string());
+    }
+
+    if data.xor_with_byte(88) != &data ^ &ASCIIData::repeated(88, data.len()) {
+      return Err(format!("wrong output: {}", data.xor_with_byte(88).to_hex_string()));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_xor_scalar() -> Result<(), String> {
+    let hex = HexData::from("1c0111001f010100061a024b53535009181c");
+    if &hex ^ 0x00 != hex {
+      return Err(format!("XOR with 0x00 gave {}", &hex ^ 0x00));
+    }
+
+    let data = ASCIIData::from("Cooking MC's like a pound of bacon");
+    let complement = &data ^ 0xff;
+    if complement.iter().zip(data.iter()).any(|(&c, &b)| c != !b) {
+      return Err(format!("XOR with 0xff gave {}", complement.to_hex_string()));
+    }
+
+    if &data ^ 88 != data.xor_with_byte(88) {
+      return Err("scalar XOR differs from xor_with_byte".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_concat() -> Result<(), String> {
+    let iv = HexData::from("00112233");
+    let block = HexData::from("1c0111001f");
+
+    let res = &iv + &block;
+    if res.to_string() != "001122331c0111001f" {
+      return Err(format!("wrong output: {}", res));
+    }
+
+    let mut grown = iv.clone();
+    grown.extend(&block);
+    if grown != res {
+      return Err(format!("extend gave {} instead of {}", grown, res));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_repeated_and_cycled() -> Result<(), String> {
+    let probe = ASCIIData::repeated(b'A', 5);
+    if probe.to_string() != "AAAAA" {
+      return Err(format!("wrong output: {}", probe));
+    }
+
+    let cases = [(0, ""), (2, "IC"), (3, "ICE"), (8, "ICEICEIC")];
+    for (len, expected) in cases {
+      let res = ASCIIData::cycled(&ASCIIData::from("ICE"), len);
+      if res.to_string() != expected {
+        return Err(format!("cycled to length {} gave {}", len, res));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_from_reader() -> Result<(), String> {
+    let path = std::env::temp_dir().join(format!("cryptopals_from_reader_{}.bin", std::process::id()));
+    std::fs::write(&path, b"YELLOW\x00SUBMARINE\xff").map_err(|e| e.to_string())?;
+
+    let res = ASCIIData::from_reader(File::open(&path).map_err(|e| e.to_string())?);
+    std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+
+    let data = res.map_err(|e| e.to_string())?;
+    if data.bytes() != b"YELLOW\x00SUBMARINE\xff" {
+      return Err(format!("wrong output: {:?}", data.bytes()));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_write() -> Result<(), String> {
+    let mut buffer = ASCIIData::from("YELLOW ");
+    write!(buffer, "SUB").map_err(|e| e.to_string())?;
+    io::copy(&mut "MARINE".as_bytes(), &mut buffer).map_err(|e| e.to_string())?;
+
+    if buffer.bytes() != b"YELLOW SUBMARINE" {
+      return Err(format!("wrong output: {}", buffer));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  #[cfg(feature = "rand")]
+  fn test_random() -> Result<(), String> {
+    let key1 = ASCIIData::random(16);
+    let key2 = ASCIIData::random(16);
+
+    if key1.len() != 16 || key2.len() != 16 {
+      return Err(format!("wrong lengths: {} and {}", key1.len(), key2.len()));
+    }
+
+    if key1 == key2 {
+      return Err("two random keys are identical".to_string());
+    }
+
+    for _ in 0..100 {
+      let prefix = ASCIIData::random_in(5..11);
+      if prefix.len() < 5 || prefix.len() >= 11 {
+        return Err(format!("length {} out of range", prefix.len()));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_hex_dump() -> Result<(), String> {
+    let mut bytes = Vec::from("YELLOW SUBMARINE");
+    bytes.extend(0u8..16);
+
+    let result = "00000000: 5945 4c4c 4f57 2053 5542 4d41 5249 4e45  YELLOW SUBMARINE\n".to_string()
+      + "00000010: 0001 0203 0405 0607 0809 0a0b 0c0d 0e0f  ................";
+    let res = format!("{:#}", ASCIIData::from(bytes));
+
+    if res != result {
+      return Err(format!("wrong output:\n{}", res));
+    }
+
+    let res = format!("{:#}", ASCIIData::from("ICE!"));
+    if res != "00000000: 4943 4521                                ICE!" {
+      return Err(format!("wrong output:\n{}", res));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_display_invalid_utf8() -> Result<(), String> {
+    let data = ASCIIData::from(vec![0xFF, 0x00]);
+
+    if data.to_string() != "\u{FFFD}\u{0}" {
+      return Err(format!("wrong output: {:?}", data.to_string()));
+    }
+
+    if data.try_to_string().is_ok() {
+      return Err("strict conversion accepted invalid UTF-8".to_string());
+    }
+
+    if ASCIIData::from("ICE").try_to_string() != Ok("ICE".to_string()) {
+      return Err("strict conversion rejected valid UTF-8".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_lookup_decode_matches_branches() -> Result<(), String> {
+    // The range checks ascii_to_byte used before the reverse lookup tables, for valid characters.
+    fn branchy_b64(c: u8) -> u8 {
+      match c {
+        b'A'..=b'Z' => c - 65,
+        b'a'..=b'z' => 26 + (c - 97),
+        b'0'..=b'9' => 52 + (c - 48),
+        b'+' => 62,
+        _ => 63
+      }
+    }
+
+    fn branchy_hex(c: u8) -> u8 {
+      if c.is_ascii_digit() {
+        c - 48
+      } else {
+        10 + (c.to_ascii_lowercase() - 97)
+      }
+    }
+
+    let b64_alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let hex_alphabet = b"0123456789abcdefABCDEF";
+    let n = 1 << 20;
+    let b64_text = Vec::from_iter((0..n).map(|i| b64_alphabet[(i * 7 + i / 251) % 64]));
+    let hex_text = Vec::from_iter((0..n).map(|i| hex_alphabet[(i * 13 + i / 257) % 22]));
+
+    let (b64, hex) = (StandardBase64::default(), StandardBase16::default());
+    let b64_branchy = Vec::from_iter(b64_text.iter().map(|&c| branchy_b64(c)));
+    let hex_branchy = Vec::from_iter(hex_text.iter().map(|&c| branchy_hex(c)));
+    if b64.ascii_to_bytes(&b64_text) != b64_branchy || hex.ascii_to_bytes(&hex_text) != hex_branchy {
+      return Err("lookup decoding disagrees with the range checks".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_xor_matches_bytewise() -> Result<(), String> {
+    fn bytewise_xor(a: &[u8], b: &[u8]) -> Vec<u8> { Vec::from_iter(a.iter().zip(b).map(|(x, y)| x ^ y)) }
+
+    // Cover empty input, a tail with no full word, and every tail length after a full word.
+    for n in 0..=24 {
+      let a = Vec::from_iter((0..n).map(|i| (37 * i + 11) as u8));
+      let b = Vec::from_iter((0..n).map(|i| (101 * i + 3) as u8));
+      let res = ASCIIData::from(a.clone()) ^ ASCIIData::from(b.clone());
+
+      if *res.bytes() != bytewise_xor(&a, &b) {
+        return Err(format!("wrong output for length {}", n));
+      }
+    }
+
+    // 1 MiB plus a ragged tail.
+    let n = (1 << 20) + 5;
+    let a = Vec::from_iter((0..n).map(|i| (i * 7 + i / 251) as u8));
+    let b = Vec::from_iter((0..n).map(|i| (i * 13 + i / 257) as u8));
+    let (data_a, data_b) = (ASCIIData::from(a.clone()), ASCIIData::from(b.clone()));
+
+    if *(&data_a ^ &data_b).bytes() != bytewise_xor(&a, &b) {
+      return Err("wrong output for 1 MiB buffers".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_base32_round_trip() -> Result<(), String> {
+    let vectors = [
+      ("", ""),
+      ("f", "MY======"),
+      ("fo", "MZXQ===="),
+      ("foo", "MZXW6==="),
+      ("foob", "MZXW6YQ="),
+      ("fooba", "MZXW6YTB"),
+      ("foobar", "MZXW6YTBOI======")
+    ];
+
+    for (plain, encoded) in vectors {
+      let res = Base32Data::from(&ASCIIData::from(plain));
+      if res.to_string() != encoded {
+        return Err(format!("{:?} encodes to {}", plain, res));
+      }
+
+      let res = ASCIIData::from_base32_data(&Base32Data::from(encoded));
+      if res.to_string() != plain {
+        return Err(format!("{} decodes to {:?}", encoded, res.to_string()));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_base32_invalid_char() -> Result<(), String> {
+    match Base32Data::try_from_ascii("MZ#W6===") {
+      Err(DecodeError::InvalidChar { byte: b'#', index: 2 }) => {},
+      res => return Err(format!("invalid character gave {:?}", res.map(|data| data.to_string())))
+    }
+
+    // The infallible path must not mistake the bad character for padding and stop there.
+    let res = ASCIIData::from_base32_data(&Base32Data::from("MZ#W6==="));
+    if res.len() != 3 {
+      return Err(format!("decoded {} bytes instead of 3", res.len()));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_byte_histogram() -> Result<(), String> {
+    let counts = ASCIIData::from("aaab").byte_histogram();
+    if counts[b'a' as usize] != 3 || counts[b'b' as usize] != 1 || counts.iter().sum::<u64>() != 4 {
+      return Err(format!(
+        "wrong counts: {} a, {} b, {} total",
+        counts[b'a' as usize],
+        counts[b'b' as usize],
+        counts.iter().sum::<u64>()
+      ));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_popcount() -> Result<(), String> {
+    let data = ASCIIData::from(vec![0xFF, 0x0F]);
+
+    if data.popcount() != 12 {
+      return Err(format!("wrong popcount: {}", data.popcount()));
+    }
+
+    if data.hamming_weight_normalized() != 0.75 || ASCIIData::from("").hamming_weight_normalized() != 0.0 {
+      return Err(format!("wrong normalized weight: {}", data.hamming_weight_normalized()));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_as_ref_and_deref() -> Result<(), String> {
+    fn checksum(bytes: impl AsRef<[u8]>) -> u32 { bytes.as_ref().iter().map(|&b| b as u32).sum() }
+
+    let data = ASCIIData::from("ICE");
+    if checksum(&data) != 73 + 67 + 69 || hex::encode(&data) != "494345" {
+      return Err("wrong bytes through AsRef".to_string());
+    }
+
+    if data.first() != Some(&73) || !data.starts_with(b"IC") {
+      return Err("wrong bytes through Deref".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_bytes_to_b64() -> Result<(), String> {
+    let vectors = [("", ""), ("M", "TQ"), ("Ma", "TWE"), ("Man", "TWFu"), ("I'm killing", "SSdtIGtpbGxpbmc")];
+
+    for (plain, encoded) in vectors {
+      let res = B64Data::from(&ASCIIData::from(plain)).to_string();
+      if res != encoded {
+        return Err(format!("{:?} encodes to {}", plain, res));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_to_base64_string() -> Result<(), String> {
+    let cases =
+      [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("foobar", "Zm9vYmFy")];
+    for (text, expected) in cases {
+      let data = ASCIIData::from(text);
+      let res = data.to_base64_string();
+      if res != expected {
+        return Err(format!("encoded {:?} as {} instead of {}", text, res, expected));
+      }
+
+      if ASCIIData::from_base64(&res) != Ok(data) {
+        return Err(format!("{} does not decode back to {:?}", res, text));
+      }
+    }
+
+    let data = ASCIIData::from([0xde, 0xad, 0xbe, 0xef]);
+    if data.to_bytes() != vec![0xde, 0xad, 0xbe, 0xef] || data.to_base64_string() != "3q2+7w==" {
+      return Err(format!("got bytes {:?} and base64 {}", data.to_bytes(), data.to_base64_string()));
+    }
+
+    // The encoded representations give the base64 of the bytes their symbols stand for, not of the symbol values
+    // they store.
+    let encoded = [
+      ("hex", HexData::from("deadbeef").to_base64_string()),
+      ("base64", B64Data::from("3q2+7w").to_base64_string()),
+      ("base32", Base32Data::from("32W353Y=").to_base64_string())
+    ];
+    for (name, res) in encoded {
+      if res != "3q2+7w==" {
+        return Err(format!("{} encoded to {}", name, res));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_parse_b64() -> Result<(), String> {
+    for (input, expected) in [("SSdtIGtp", "SSdtIGtp"), ("SSdtIGs=", "SSdtIGs"), ("SSdtIA==", "SSdtIA"), ("", "")] {
+      let res = input.parse::<B64Data>().map_err(|e| e.to_string())?;
+      if res != B64Data::from(expected) {
+        return Err(format!("{} parsed to {}", input, res));
+      }
+    }
+
+    let cases = [
+      ("SSdt IGtp", DecodeError::InvalidChar { byte: b' ', index: 4 }),
+      ("SSd=tIGs", DecodeError::InvalidChar { byte: b'=', index: 3 }),
+      ("SSdtIGs==", DecodeError::BadPadding),
+      ("SSdtI", DecodeError::BadLength { len: 5 })
+    ];
+
+    for (input, expected) in cases {
+      match input.parse::<B64Data>() {
+        Err(e) if e == expected => {},
+        res => return Err(format!("{} gave {:?}", input, res.map(|data| data.to_string())))
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_from_base64() -> Result<(), String> {
+    let cases = [("SSdt", "I'm"), ("SSdtIGs=", "I'm k"), ("SSdtIA==", "I'm "), ("", "")];
+    for (input, expected) in cases {
+      let res = ASCIIData::from_base64(input).map_err(|e| e.to_string())?;
+      if res.bytes() != expected.as_bytes() {
+        return Err(format!("{} decodes to {}", input, res));
+      }
+    }
+
+    match ASCIIData::from_base64("SSdt=IGs") {
+      Err(DecodeError::InvalidChar { byte: b'=', index: 4 }) => {},
+      res => return Err(format!("misplaced padding gave {:?}", res.map(|data| data.to_string())))
+    }
+
+    for input in ["SSdtIGs==", "SSdtIA=", "SSdt="] {
+      match ASCIIData::from_base64(input) {
+        Err(DecodeError::BadPadding) => {},
+        res => return Err(format!("{} gave {:?}", input, res.map(|data| data.to_string())))
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_from_b64_multiline() -> Result<(), String> {
+    let expected = "I'm killing your brain like a poisonous mushroom";
+    let wrapped = [
+      "SSdtIGtpbGxpbmcgeW91ciBicmFp\nbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t\n",
+      "SSdtIGtpbGxpbmcgeW91ciBicmFp\r\nbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t\r\n",
+      "  SSdtIGtpbGxpbmcg\n\neW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t"
+    ];
+
+    for (i, text) in wrapped.iter().enumerate() {
+      let res = ASCIIData::from_b64_multiline(text).map_err(|e| e.to_string())?.to_string();
+      if res != expected {
+        return Err(format!("input {} yields wrong output: {}", i, res));
+      }
+    }
+
+    let padded = ASCIIData::from_b64_multiline("SSdt\nIGtp\nbGw=\n").map_err(|e| e.to_string())?.to_string();
+    if padded != "I'm kill" {
+      return Err(format!("padded input yields wrong output: {}", padded));
+    }
+
+    // Whitespace is skipped before validating, so the index is into the symbols alone.
+    match ASCIIData::from_b64_multiline("SSdt\nIG#p\n") {
+      Err(DecodeError::InvalidChar { byte: b'#', index: 6 }) => {},
+      res => return Err(format!("invalid input gave {:?}", res.map(|data| data.to_string())))
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_encode_b64_stream() -> Result<(), String> {
+    // Lengths around the 3-byte group size and the internal buffer size, plus one large file.
+    for n in [0, 1, 2, 3, 3071, 3072, 3073, 3074, 1 << 20] {
+      let bytes = Vec::from_iter((0..n).map(|i| (i * 31 + i / 7) as u8));
+      let expected = B64Data::from(&ASCIIData::from(bytes.clone())).to_string();
+
+      let path = std::env::temp_dir().join(format!("cryptopals_b64_stream_{}_{}", std::process::id(), n));
+      std::fs::write(&path, &bytes).map_err(|e| e.to_string())?;
+
+      // A BufReader over the file hands out reads that don't line up with 3-byte groups.
+      let mut res = Vec::new();
+      let reader = BufReader::with_capacity(1000, File::open(&path).map_err(|e| e.to_string())?);
+      encode_b64_stream(reader, &mut res).map_err(|e| e.to_string())?;
+      std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+
+      if res != expected.as_bytes() {
+        return Err(format!("streamed output differs for length {}", n));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_is_empty_and_get() -> Result<(), String> {
+    let data = ASCIIData::from("ICE");
+
+    if data.is_empty() || !ASCIIData::from("").is_empty() {
+      return Err("wrong emptiness".to_string());
+    }
+
+    if data.get(0) != Some(73) || data.get(2) != Some(69) || data.get(3).is_some() || data.get(usize::MAX).is_some() {
+      return Err("wrong bounds-checked access".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn test_serde_round_trip() -> Result<(), String> {
+    let hex = HexData::from("1c0111001f010100061a024b53535009181c");
+
+    let json = serde_json::to_string(&hex).map_err(|e| e.to_string())?;
+    if json != "\"1c0111001f010100061a024b53535009181c\"" {
+      return Err(format!("wrong JSON: {}", json));
+    }
+
+    let res: HexData = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    if res != hex {
+      return Err(format!("wrong round trip: {}", res));
+    }
+
+    let b64: B64Data = serde_json::from_str("\"SSdtIGs=\"").map_err(|e| e.to_string())?;
+    if ASCIIData::from_b64_data(&b64) != ASCIIData::from("I'm k") {
+      return Err(format!("padded base64 deserialized to {}", b64));
+    }
+
+    let res: B64Data = serde_json::from_str(&serde_json::to_string(&b64).map_err(|e| e.to_string())?)
+      .map_err(|e| e.to_string())?;
+    if res != b64 {
+      return Err(format!("wrong base64 round trip: {}", res));
+    }
+
+    if serde_json::from_str::<B64Data>("\"SSdt#Gtp\"").is_ok() {
+      return Err("invalid base64 deserialized".to_string());
+    }
+
+    if serde_json::from_str::<HexData>("\"1c0\"").is_ok() {
+      return Err("odd-length hex deserialized".to_string());
+    }
+
+    return Ok(());
+  }
+
   #[test]
   fn test_hex_xor() -> Result<(), String> {
     let hex1 = super::HexData::from("1c0111001f010100061a024b53535009181c".to_string());
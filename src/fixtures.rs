@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use crate::data::*;
+
+// Challenge files are read from disk at most once per process and shared between every test that loads them.
+static CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+// Reads files/<name> relative to the crate root, so it works whatever directory the tests are run from.
+fn read_fixture(name: &str) -> io::Result<String> {
+  let mut cache = CACHE.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+  if let Some(text) = cache.get(name) {
+    return Ok(text.clone());
+  }
+
+  let text = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("files").join(name))?;
+  cache.insert(name.to_string(), text.clone());
+  return Ok(text);
+}
+
+// Each line of files/<name> as-is, without its line ending; decoding (e.g. from hex) is left to the caller.
+#[allow(dead_code)]
+pub fn load_lines(name: &str) -> io::Result<Vec<ASCIIData>> {
+  Ok(Vec::from_iter(read_fixture(name)?.lines().map(ASCIIData::from)))
+}
+
+// The decoded contents of a line-wrapped base64 file such as those for Challenges 6, 7 and 10. Malformed base64 is
+// reported as InvalidData.
+#[allow(dead_code)]
+pub fn load_b64(name: &str) -> io::Result<ASCIIData> {
+  ASCIIData::from_b64_multiline(&read_fixture(name)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_load_lines() -> Result<(), String> {
+    let lines = load_lines("c4s1.txt").map_err(|e| e.to_string())?;
+    if lines.len() != 327 {
+      return Err(format!("wrong line count: {}", lines.len()));
+    }
+
+    if lines[170].to_string() != "7b5a4215415d544115415d5015455447414c155c46155f4058455c5b523f" {
+      return Err(format!("wrong line 170: {}", lines[170]));
+    }
+
+    if load_lines("c4s1.txt").map_err(|e| e.to_string())? != lines {
+      return Err("cached load differs from the first".to_string());
+    }
+
+    if load_lines("no_such_file.txt").is_ok() {
+      return Err("loading a missing file should fail".to_string());
+    }
+
+    return Ok(());
+  }
+}
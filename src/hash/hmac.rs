@@ -0,0 +1,84 @@
+use crate::data::*;
+use crate::hash::sha256::*;
+use crate::util::*;
+
+// HMAC (RFC 2104) over SHA-256: H((K ^ opad) || H((K ^ ipad) || message)), where K is the key zero-padded to the block
+// size, after first hashing it if it is longer than a block.
+#[allow(dead_code)]
+pub fn hmac_sha256(key: &ASCIIData, message: &ASCIIData) -> ASCIIData {
+  let key = if key.len() > BLOCK_SIZE { sha256(key) } else { key.clone() };
+  let key = key.pad_to(BLOCK_SIZE, 0x00).expect("keys are at most one block long after hashing");
+
+  let mut inner = Sha256::new();
+  inner.update(&(&key ^ 0x36));
+  inner.update(message);
+
+  let mut outer = Sha256::new();
+  outer.update(&(&key ^ 0x5c));
+  outer.update(&inner.finalize());
+  return outer.finalize();
+}
+
+// Checks a MAC received alongside message. The comparison goes through secure_compare, so how long a rejection takes
+// does not reveal how many leading bytes of the MAC were right.
+#[allow(dead_code)]
+pub fn verify_hmac_sha256(key: &ASCIIData, message: &ASCIIData, mac: &ASCIIData) -> bool {
+  secure_compare(&hmac_sha256(key, message), mac)
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  // Test cases 1, 2 and 6 from RFC 4231, covering a short key, a key shorter than the output and a key longer than a
+  // block.
+  #[test]
+  fn test_hmac_sha256_vectors() -> Result<(), String> {
+    let cases = [
+      (ASCIIData::repeated(0x0b, 20), "Hi There", "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"),
+      (
+        ASCIIData::from("Jefe"),
+        "what do ya want for nothing?",
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+      ),
+      (
+        ASCIIData::repeated(0xaa, 131),
+        "Test Using Larger Than Block-Size Key - Hash Key First",
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+      )
+    ];
+
+    for (key, message, mac) in cases {
+      let res = hmac_sha256(&key, &ASCIIData::from(message)).to_hex_string();
+      if res != mac {
+        return Err(format!("HMAC of {:?} = {}", message, res));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_verify_hmac_sha256() -> Result<(), String> {
+    let key = ASCIIData::from("Jefe");
+    let message = ASCIIData::from("what do ya want for nothing?");
+    let mac = hmac_sha256(&key, &message);
+
+    if !verify_hmac_sha256(&key, &message, &mac) {
+      return Err("valid MAC was rejected".to_string());
+    }
+
+    let mut first = mac.to_bytes();
+    first[0] ^= 1;
+    let mut last = mac.to_bytes();
+    last[31] ^= 1;
+
+    for forged in [ASCIIData::from(first), ASCIIData::from(last), mac.truncate(31), ASCIIData::from("")] {
+      if verify_hmac_sha256(&key, &message, &forged) {
+        return Err(format!("forged MAC {} was accepted", forged.to_hex_string()));
+      }
+    }
+
+    return Ok(());
+  }
+}
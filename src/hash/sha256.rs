@@ -0,0 +1,184 @@
+use crate::data::*;
+
+const ROUND_CONSTANTS: [u32; 64] = [
+  0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 0xd807aa98,
+  0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+  0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8,
+  0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+  0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819,
+  0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+  0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+  0xc67178f2
+];
+
+const INITIAL_STATE: [u32; 8] =
+  [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+pub const BLOCK_SIZE: usize = 64;
+
+// The Merkle-Damgard padding appended to a message of len bytes: a 1 bit, zeros up to 56 bytes mod 64, then the
+// message length in bits as a big-endian u64.
+#[allow(dead_code)]
+pub fn md_padding(len: u64) -> Vec<u8> {
+  let mut padding = vec![0x80];
+  padding.resize(1 + (BLOCK_SIZE + 55 - len as usize % BLOCK_SIZE) % BLOCK_SIZE, 0);
+  padding.extend((len * 8).to_be_bytes());
+  return padding;
+}
+
+// An incremental SHA-256. The chaining state is exposed, and a hasher can be resumed from any state and message length
+// with from_state, which is all a length-extension attack needs.
+#[derive(Clone)]
+pub struct Sha256 {
+  state: [u32; 8],
+  buffer: Vec<u8>,
+  len: u64
+}
+
+impl Default for Sha256 {
+  fn default() -> Self { Self::new() }
+}
+
+impl Sha256 {
+  #[allow(dead_code)]
+  pub fn new() -> Sha256 { Sha256 { state: INITIAL_STATE, buffer: Vec::with_capacity(BLOCK_SIZE), len: 0 } }
+
+  // Resumes hashing as if len bytes (a whole number of blocks, padding included) had already produced this state.
+  #[allow(dead_code)]
+  pub fn from_state(state: [u32; 8], len: u64) -> Sha256 {
+    if !len.is_multiple_of(BLOCK_SIZE as u64) {
+      panic!("Cannot resume SHA-256 from {} bytes, which is not a whole number of blocks", len);
+    }
+
+    return Sha256 { state, buffer: Vec::with_capacity(BLOCK_SIZE), len };
+  }
+
+  // Splits a digest back into the chaining state it was serialized from.
+  #[allow(dead_code)]
+  pub fn state_from_digest(digest: &ASCIIData) -> [u32; 8] {
+    if digest.len() != 32 {
+      panic!("Cannot read a SHA-256 state out of {} bytes", digest.len());
+    }
+
+    return std::array::from_fn(|i| u32::from_be_bytes(digest[4 * i..4 * i + 4].try_into().unwrap()));
+  }
+
+  #[allow(dead_code)]
+  pub fn state(&self) -> [u32; 8] { self.state }
+
+  #[allow(dead_code)]
+  pub fn update(&mut self, data: &[u8]) {
+    self.len += data.len() as u64;
+    self.buffer.extend_from_slice(data);
+
+    let whole = self.buffer.len() - self.buffer.len() % BLOCK_SIZE;
+    for block in self.buffer[..whole].chunks_exact(BLOCK_SIZE) {
+      compress(&mut self.state, block.try_into().unwrap());
+    }
+
+    self.buffer.drain(..whole);
+  }
+
+  #[allow(dead_code)]
+  pub fn finalize(mut self) -> ASCIIData {
+    let padding = md_padding(self.len);
+    self.update(&padding);
+    return ASCIIData::from_iter(self.state.iter().flat_map(|word| word.to_be_bytes()));
+  }
+}
+
+fn compress(state: &mut [u32; 8], block: &[u8; BLOCK_SIZE]) {
+  let mut w = [0u32; 64];
+  for i in 0..16 {
+    w[i] = u32::from_be_bytes(block[4 * i..4 * i + 4].try_into().unwrap());
+  }
+
+  for i in 16..64 {
+    let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+    let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+    w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+  }
+
+  let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+  for i in 0..64 {
+    let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+    let ch = (e & f) ^ (!e & g);
+    let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(ROUND_CONSTANTS[i]).wrapping_add(w[i]);
+    let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+    let maj = (a & b) ^ (a & c) ^ (b & c);
+    let t2 = s0.wrapping_add(maj);
+
+    (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+  }
+
+  for (word, x) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+    *word = word.wrapping_add(x);
+  }
+}
+
+#[allow(dead_code)]
+pub fn sha256(message: &ASCIIData) -> ASCIIData {
+  let mut hasher = Sha256::new();
+  hasher.update(message);
+  return hasher.finalize();
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sha256_vectors() -> Result<(), String> {
+    let cases = [
+      ("", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+      ("abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+      (
+        "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+      )
+    ];
+
+    for (message, digest) in cases {
+      let res = sha256(&ASCIIData::from(message)).to_hex_string();
+      if res != digest {
+        return Err(format!("sha256({:?}) = {}", message, res));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_sha256_incremental() -> Result<(), String> {
+    let message = ASCIIData::repeated(b'a', 1000);
+    let mut hasher = Sha256::new();
+    for chunk in message.chunks(37) {
+      hasher.update(chunk);
+    }
+
+    if hasher.finalize() != sha256(&message) {
+      return Err("incremental hash differs from one-shot hash".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_sha256_resume_from_state() -> Result<(), String> {
+    let secret_and_message = ASCIIData::from("YELLOW SUBMARINEcomment1=cooking%20MCs");
+    let extension = ASCIIData::from(";admin=true");
+    let digest = sha256(&secret_and_message);
+
+    let glue = md_padding(secret_and_message.len() as u64);
+    let mut forger =
+      Sha256::from_state(Sha256::state_from_digest(&digest), (secret_and_message.len() + glue.len()) as u64);
+    forger.update(&extension);
+
+    let expected = sha256(&(&(&secret_and_message + &ASCIIData::from(glue)) + &extension));
+    if forger.finalize() != expected {
+      return Err("resuming from a digest does not extend the message".to_string());
+    }
+
+    return Ok(());
+  }
+}
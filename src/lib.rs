@@ -0,0 +1,11 @@
+#![allow(clippy::needless_return)]
+#![allow(clippy::manual_range_contains, clippy::needless_range_loop, clippy::useless_vec)]
+
+pub mod attack;
+pub mod crypto;
+pub mod data;
+pub mod fixtures;
+pub mod hash;
+pub mod math;
+pub mod pubkey;
+pub mod util;
@@ -1,4 +0,0 @@
-mod attack;
-mod data;
-
-fn main() {}
@@ -0,0 +1,80 @@
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Zero};
+
+use crate::math::modular::crt;
+
+// Finds x in [0, order) with g^x = h (mod p) by trying every exponent. Only meant for the small prime orders that
+// Pohlig-Hellman reduces to.
+fn brute_force_dlog(h: &BigUint, g: &BigUint, p: &BigUint, order: &BigUint) -> Option<BigUint> {
+  let mut power = BigUint::one();
+  let mut x = BigUint::zero();
+
+  while x < *order {
+    if power == *h {
+      return Some(x);
+    }
+
+    power = power * g % p;
+    x += 1u32;
+  }
+
+  return None;
+}
+
+// Solves g^x = h (mod p) where factors is the prime factorization (with multiplicity) of the order of g, e.g. from
+// factorize, and every prime in it is small enough to brute force. Each prime power q^e is handled one base-q digit at
+// a time and the results are combined with CRT, giving x modulo the order of g. Panics if h is not a power of g.
+#[allow(dead_code)]
+pub fn pohlig_hellman_small(h: &BigUint, g: &BigUint, p: &BigUint, factors: &[BigUint]) -> BigUint {
+  let order = factors.iter().fold(BigUint::one(), |acc, q| acc * q);
+  let mut primes = factors.to_vec();
+  primes.sort();
+  primes.dedup();
+
+  let mut residues = vec![];
+  for q in primes {
+    let e = factors.iter().filter(|&f| *f == q).count() as u32;
+    let q_e = q.pow(e);
+
+    // Move into the subgroup of order q^e, then peel off digits using the element gamma of order q.
+    let cofactor = &order / &q_e;
+    let (g0, h0) = (g.modpow(&cofactor, p), h.modpow(&cofactor, p));
+    let gamma = g0.modpow(&q.pow(e - 1), p);
+
+    let mut x = BigUint::zero();
+    for k in 0..e {
+      let shifted = g0.modpow(&((&q_e - &x % &q_e) % &q_e), p) * &h0 % p;
+      let h_k = shifted.modpow(&q.pow(e - 1 - k), p);
+      let digit = brute_force_dlog(&h_k, &gamma, p, &q).expect("h is not in the subgroup generated by g");
+      x += digit * q.pow(k);
+    }
+
+    residues.push((BigInt::from(x), BigInt::from(q_e)));
+  }
+
+  return crt(&residues).unwrap().to_biguint().unwrap();
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+  use crate::math::factor::*;
+
+  #[test]
+  fn test_pohlig_hellman_small() -> Result<(), String> {
+    // p - 1 = 2^4 * 3 * 5^2 * 7 * ... * 59, and 67 generates the whole group.
+    let p = BigUint::parse_bytes(b"76910414006168505562801", 10).unwrap();
+    let g = BigUint::from(67u32);
+    let factors = factorize(&(&p - 1u32), 100);
+
+    for secret in ["0", "1", "12345678901234567890123", "76910414006168505562799"] {
+      let x = BigUint::parse_bytes(secret.as_bytes(), 10).unwrap();
+      let res = pohlig_hellman_small(&g.modpow(&x, &p), &g, &p, &factors);
+      if res != x {
+        return Err(format!("recovered {} instead of {}", res, x));
+      }
+    }
+
+    return Ok(());
+  }
+}
@@ -0,0 +1,165 @@
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::math::modular::egcd;
+
+// Bases for Miller-Rabin. They make the test deterministic below 3.3 * 10^24 and leave a vanishing error chance above.
+const MILLER_RABIN_BASES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+fn gcd(a: &BigUint, b: &BigUint) -> BigUint {
+  let (g, _, _) = egcd(&BigInt::from(a.clone()), &BigInt::from(b.clone()));
+  return g.to_biguint().unwrap();
+}
+
+#[allow(dead_code)]
+pub fn is_probable_prime(n: &BigUint) -> bool {
+  if *n < BigUint::from(2u32) {
+    return false;
+  }
+
+  for base in MILLER_RABIN_BASES {
+    if *n == BigUint::from(base) {
+      return true;
+    }
+
+    if (n % base).is_zero() {
+      return false;
+    }
+  }
+
+  // Write n - 1 = d * 2^s with d odd.
+  let n_minus_one = n - 1u32;
+  let s = n_minus_one.trailing_zeros().unwrap();
+  let d = &n_minus_one >> s;
+
+  'bases: for base in MILLER_RABIN_BASES {
+    let mut x = BigUint::from(base).modpow(&d, n);
+    if x.is_one() || x == n_minus_one {
+      continue;
+    }
+
+    for _ in 1..s {
+      x = x.modpow(&BigUint::from(2u32), n);
+      if x == n_minus_one {
+        continue 'bases;
+      }
+    }
+
+    return false;
+  }
+
+  return true;
+}
+
+// Pollard's rho with Floyd cycle detection, retrying with a new polynomial x^2 + c whenever a walk collapses onto n
+// itself. Finds a nontrivial factor of an odd composite n.
+fn pollard_rho(n: &BigUint) -> BigUint {
+  let step = |x: &BigUint, c: &BigUint| (x * x + c) % n;
+
+  for c in 1u32.. {
+    let c = BigUint::from(c);
+    let (mut x, mut y) = (BigUint::from(2u32), BigUint::from(2u32));
+    let mut d = BigUint::one();
+
+    while d.is_one() {
+      x = step(&x, &c);
+      y = step(&step(&y, &c), &c);
+      d = gcd(&if x > y { &x - &y } else { &y - &x }, n);
+    }
+
+    if d != *n {
+      return d;
+    }
+  }
+
+  unreachable!()
+}
+
+// The prime factors of n with multiplicity, in ascending order. Trial division takes out every prime factor up to
+// bound, and Pollard's rho splits whatever is left, so bound only affects speed as long as the large factors are few.
+#[allow(dead_code)]
+pub fn factorize(n: &BigUint, bound: u64) -> Vec<BigUint> {
+  let mut factors = vec![];
+  let mut rest = n.clone();
+
+  for p in 2..=bound {
+    if rest.is_one() || rest.to_u64().is_some_and(|r| (p as u128) * (p as u128) > r as u128) {
+      break;
+    }
+
+    while !rest.is_zero() && (&rest % p).is_zero() {
+      factors.push(BigUint::from(p));
+      rest /= p;
+    }
+  }
+
+  let mut composites = vec![rest];
+  while let Some(m) = composites.pop() {
+    if m <= BigUint::one() {
+      continue;
+    }
+
+    if is_probable_prime(&m) {
+      factors.push(m);
+    } else if m.trailing_zeros() > Some(0) {
+      factors.push(BigUint::from(2u32));
+      composites.push(m >> 1);
+    } else {
+      let d = pollard_rho(&m);
+      composites.push(&m / &d);
+      composites.push(d);
+    }
+  }
+
+  factors.sort();
+
+  return factors;
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_is_probable_prime() -> Result<(), String> {
+    let primes: [u64; 6] = [2, 3, 37, 1000003, 1000000007, 18446744073709551557];
+    let composites: [u64; 6] = [0, 1, 4, 561, 1000003 * 1000000007, 3215031751];
+
+    for p in primes {
+      if !is_probable_prime(&BigUint::from(p)) {
+        return Err(format!("{} should be prime", p));
+      }
+    }
+
+    for c in composites {
+      if is_probable_prime(&BigUint::from(c)) {
+        return Err(format!("{} should be composite", c));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_factorize() -> Result<(), String> {
+    let primes: [u64; 9] = [2, 2, 2, 3, 7, 11, 11, 1000003, 1000000007];
+    let n = primes.iter().fold(BigUint::one(), |acc, &p| acc * p);
+
+    for bound in [100, 1 << 16] {
+      let factors = factorize(&n, bound);
+      if factors != Vec::from_iter(primes.iter().map(|&p| BigUint::from(p))) {
+        return Err(format!("factorize({}, {}) = {:?}", n, bound, factors));
+      }
+    }
+
+    if factorize(&BigUint::from(97u32), 10) != vec![BigUint::from(97u32)] {
+      return Err("a prime should be its own factorization".to_string());
+    }
+
+    if !factorize(&BigUint::one(), 10).is_empty() {
+      return Err("1 should have no prime factors".to_string());
+    }
+
+    return Ok(());
+  }
+}
@@ -0,0 +1,4 @@
+pub mod dlog;
+pub mod factor;
+pub mod modular;
+pub mod roots;
@@ -0,0 +1,249 @@
+use num_bigint::{BigInt, BigUint};
+use num_traits::{Euclid, One, Signed, Zero};
+
+// The extended Euclidean algorithm: returns (g, x, y) with g = gcd(a, b) >= 0 and a * x + b * y = g.
+#[allow(dead_code)]
+pub fn egcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+  let (mut old_r, mut r) = (a.clone(), b.clone());
+  let (mut old_x, mut x) = (BigInt::one(), BigInt::zero());
+  let (mut old_y, mut y) = (BigInt::zero(), BigInt::one());
+
+  while !r.is_zero() {
+    let q = &old_r / &r;
+    (old_r, r) = (r.clone(), &old_r - &q * &r);
+    (old_x, x) = (x.clone(), &old_x - &q * &x);
+    (old_y, y) = (y.clone(), &old_y - &q * &y);
+  }
+
+  if old_r.is_negative() {
+    return (-old_r, -old_x, -old_y);
+  }
+
+  return (old_r, old_x, old_y);
+}
+
+// The inverse of a modulo m in [0, m), or None if a and m are not coprime. Panics unless m is positive.
+#[allow(dead_code)]
+pub fn invmod(a: &BigInt, m: &BigInt) -> Option<BigInt> {
+  if !m.is_positive() {
+    panic!("Cannot invert modulo non-positive modulus {}", m);
+  }
+
+  let (g, x, _) = egcd(a, m);
+  if !g.is_one() {
+    return None;
+  }
+
+  return Some(x.rem_euclid(m));
+}
+
+// Combines congruences x = residue (mod modulus), given as (residue, modulus) pairs, into the smallest non-negative x
+// satisfying all of them. The moduli need not be coprime; None means the congruences contradict each other. Panics
+// unless every modulus is positive.
+#[allow(dead_code)]
+pub fn crt(residues: &[(BigInt, BigInt)]) -> Option<BigInt> {
+  let (mut x, mut m) = (BigInt::zero(), BigInt::one());
+
+  for (a, n) in residues {
+    if !n.is_positive() {
+      panic!("Cannot solve a congruence modulo non-positive modulus {}", n);
+    }
+
+    // Solve x + m * t = a (mod n) for t; this needs gcd(m, n) to divide a - x.
+    let (g, p, _) = egcd(&m, n);
+    let diff = a - &x;
+    if !(&diff % &g).is_zero() {
+      return None;
+    }
+
+    let step = n / &g;
+    let t = (diff / &g * p).rem_euclid(&step);
+    x += &m * t;
+    m *= step;
+  }
+
+  return Some(x.rem_euclid(&m));
+}
+
+// A square root of a modulo an odd prime p, by Tonelli-Shanks, or None if a is not a quadratic residue. The other root
+// is p minus this one.
+#[allow(dead_code)]
+pub fn sqrt_mod(a: &BigInt, p: &BigInt) -> Option<BigInt> {
+  let a = a.rem_euclid(p);
+  if a.is_zero() {
+    return Some(a);
+  }
+
+  let p_minus_one: BigInt = p - 1;
+  let is_residue = |n: &BigInt| n.modpow(&(&p_minus_one >> 1), p).is_one();
+  if !is_residue(&a) {
+    return None;
+  }
+
+  // Write p - 1 = q * 2^s with q odd, and find any non-residue z.
+  let s = p_minus_one.trailing_zeros().unwrap();
+  let q = &p_minus_one >> s;
+  let mut z = BigInt::from(2);
+  while is_residue(&z) {
+    z += 1;
+  }
+
+  let mut m = s;
+  let mut c = z.modpow(&q, p);
+  let mut t = a.modpow(&q, p);
+  let mut r = a.modpow(&((&q + 1) >> 1), p);
+
+  while !t.is_one() {
+    // Find the least i with t^(2^i) = 1, then fold in a correction of order 2^(i+1).
+    let mut i = 0;
+    let mut t_pow = t.clone();
+    while !t_pow.is_one() {
+      t_pow = &t_pow * &t_pow % p;
+      i += 1;
+    }
+
+    let b = c.modpow(&(BigInt::one() << (m - i - 1)), p);
+    m = i;
+    c = &b * &b % p;
+    t = t * &c % p;
+    r = r * b % p;
+  }
+
+  return Some(r);
+}
+
+// base^exp mod modulus by a Montgomery ladder, agreeing with BigUint::modpow. Every exponent bit costs one multiply
+// and one square whichever value it has; only which register each result lands in depends on the bit. That evens out
+// the per-bit work of square-and-multiply, but the iteration count still reveals the exponent's bit length, and
+// BigUint arithmetic itself takes time depending on its operands, so this is only constant-ish time. Panics if modulus
+// is zero.
+#[allow(dead_code)]
+pub fn modexp_ladder(base: &BigUint, exp: &BigUint, modulus: &BigUint) -> BigUint {
+  if modulus.is_zero() {
+    panic!("Cannot exponentiate modulo zero");
+  }
+
+  let (mut r0, mut r1) = (BigUint::one() % modulus, base % modulus);
+  for i in (0..exp.bits()).rev() {
+    let product = &r0 * &r1 % modulus;
+    if exp.bit(i) {
+      (r0, r1) = (product, &r1 * &r1 % modulus);
+    } else {
+      (r0, r1) = (&r0 * &r0 % modulus, product);
+    }
+  }
+
+  return r0;
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  #[allow(dead_code)]
+  fn big(n: i64) -> BigInt { BigInt::from(n) }
+
+  #[test]
+  fn test_egcd() -> Result<(), String> {
+    for (a, b, gcd) in [(240, 46, 2), (17, 3120, 1), (-17, 3120, 1), (0, 5, 5), (12, 0, 12)] {
+      let (g, x, y) = egcd(&big(a), &big(b));
+      if g != big(gcd) || big(a) * &x + big(b) * &y != g {
+        return Err(format!("egcd({}, {}) = ({}, {}, {})", a, b, g, x, y));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_invmod() -> Result<(), String> {
+    if invmod(&big(17), &big(3120)) != Some(big(2753)) {
+      return Err(format!("invmod(17, 3120) = {:?}", invmod(&big(17), &big(3120))));
+    }
+
+    if invmod(&big(-17), &big(3120)) != Some(big(3120 - 2753)) {
+      return Err(format!("invmod(-17, 3120) = {:?}", invmod(&big(-17), &big(3120))));
+    }
+
+    if invmod(&big(6), &big(3120)).is_some() {
+      return Err("6 should have no inverse modulo 3120".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_crt() -> Result<(), String> {
+    let residues = [(big(2), big(3)), (big(3), big(5)), (big(2), big(7))];
+    if crt(&residues) != Some(big(23)) {
+      return Err(format!("three-congruence CRT gave {:?}", crt(&residues)));
+    }
+
+    // Non-coprime moduli: consistent congruences still combine, contradictory ones do not.
+    if crt(&[(big(2), big(4)), (big(4), big(6))]) != Some(big(10)) {
+      return Err(format!("consistent non-coprime CRT gave {:?}", crt(&[(big(2), big(4)), (big(4), big(6))])));
+    }
+
+    if crt(&[(big(1), big(4)), (big(2), big(6))]).is_some() {
+      return Err("contradictory congruences were combined".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_sqrt_mod() -> Result<(), String> {
+    // 233970423115425145524320034830162017933 is 5 mod 8 and 17 is 1 mod 16, so both need the general algorithm.
+    let p_large = BigInt::parse_bytes(b"233970423115425145524320034830162017933", 10).unwrap();
+    for p in [big(7), big(17), big(97), p_large] {
+      for a in 0..50 {
+        match sqrt_mod(&big(a), &p) {
+          Some(r) if (&r * &r - big(a)).rem_euclid(&p).is_zero() => {},
+          Some(r) => return Err(format!("sqrt_mod({}, {}) = {} is not a root", a, p, r)),
+          None if big(a).modpow(&((&p - 1) >> 1), &p).is_one() => {
+            return Err(format!("sqrt_mod({}, {}) missed a residue", a, p));
+          },
+          None => {}
+        }
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_modexp_ladder_edge_cases() -> Result<(), String> {
+    let cases: [(u64, u64, u64); 6] = [(4, 13, 497), (0, 0, 7), (5, 0, 1), (0, 5, 7), (7, 1, 7), (3, 200, 1000000007)];
+    for (base, exp, modulus) in cases {
+      let (base, exp, modulus) = (BigUint::from(base), BigUint::from(exp), BigUint::from(modulus));
+      let res = modexp_ladder(&base, &exp, &modulus);
+      if res != base.modpow(&exp, &modulus) {
+        return Err(format!("{}^{} mod {} = {}", base, exp, modulus, res));
+      }
+    }
+
+    return Ok(());
+  }
+
+  // Differential test against BigUint::modpow on seeded random inputs of up to 1024 bits.
+  #[cfg(feature = "rand")]
+  #[test]
+  fn test_modexp_ladder_matches_modpow() -> Result<(), String> {
+    use crate::data::ASCIIData;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::seed_from_u64(387);
+    for _ in 0..200 {
+      let mut random = || BigUint::from_bytes_be(&ASCIIData::random_in_with(&mut rng, 1..129));
+      let (base, exp, modulus) = (random(), random(), random() + 1u32);
+
+      let res = modexp_ladder(&base, &exp, &modulus);
+      if res != base.modpow(&exp, &modulus) {
+        return Err(format!("{}^{} mod {} = {}", base, exp, modulus, res));
+      }
+    }
+
+    return Ok(());
+  }
+}
@@ -0,0 +1,107 @@
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+// Returns the floor of the k-th root of n, and whether that root is exact (root^k == n). Newton's method from an
+// overestimate converges monotonically down to the floor; the result is then checked against its neighbours so an
+// off-by-one never slips through at perfect-power boundaries.
+#[allow(dead_code)]
+pub fn iroot(n: &BigUint, k: u32) -> (BigUint, bool) {
+  if k == 0 {
+    panic!("Cannot take the 0th root of a number");
+  }
+
+  if n.is_zero() || k == 1 {
+    return (n.clone(), true);
+  }
+
+  let k_big = BigUint::from(k);
+  let k_minus_one = BigUint::from(k - 1);
+
+  // 2^ceil(bits / k) is at least the true root.
+  let mut x = BigUint::one() << n.bits().div_ceil(k as u64);
+  loop {
+    let y = (&k_minus_one * &x + n / x.pow(k - 1)) / &k_big;
+    if y >= x {
+      break;
+    }
+
+    x = y;
+  }
+
+  while x.pow(k) > *n {
+    x -= 1u32;
+  }
+
+  while (&x + 1u32).pow(k) <= *n {
+    x += 1u32;
+  }
+
+  let exact = x.pow(k) == *n;
+  return (x, exact);
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_iroot_small() -> Result<(), String> {
+    let cases: [(u64, u32, u64, bool); 8] = [
+      (0, 3, 0, true),
+      (1, 3, 1, true),
+      (7, 3, 1, false),
+      (8, 3, 2, true),
+      (26, 3, 2, false),
+      (27, 3, 3, true),
+      (1 << 40, 5, 256, true),
+      (17, 1, 17, true)
+    ];
+
+    for (n, k, root, exact) in cases {
+      let res = iroot(&BigUint::from(n), k);
+      if res != (BigUint::from(root), exact) {
+        return Err(format!("iroot({}, {}) = {:?}", n, k, res));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_iroot_large_cubes() -> Result<(), String> {
+    let root = BigUint::parse_bytes(b"123456789012345678901234567890123456789012345678901234567890", 10).unwrap();
+    let cube = root.pow(3);
+
+    let below = iroot(&(&cube - 1u32), 3);
+    if below != (&root - 1u32, false) {
+      return Err(format!("cube - 1 gave {:?}", below));
+    }
+
+    if iroot(&cube, 3) != (root.clone(), true) {
+      return Err("perfect cube not recognized".to_string());
+    }
+
+    let above = iroot(&(&cube + 1u32), 3);
+    if above != (root.clone(), false) {
+      return Err(format!("cube + 1 gave {:?}", above));
+    }
+
+    let next = (&root + 1u32).pow(3);
+    if iroot(&(&next - 1u32), 3) != (root.clone(), false) {
+      return Err("(root + 1)^3 - 1 rounded up".to_string());
+    }
+
+    // Cross-check against num-bigint's own root over a spread of magnitudes.
+    for bits in (64..2048).step_by(61) {
+      let n = (BigUint::one() << bits) - 12345u32;
+      for k in [2, 3, 5, 17] {
+        let (res, _) = iroot(&n, k);
+        if res != n.nth_root(k) {
+          return Err(format!("iroot(2^{} - 12345, {}) disagrees with nth_root", bits, k));
+        }
+      }
+    }
+
+    return Ok(());
+  }
+}
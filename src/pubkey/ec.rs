@@ -0,0 +1,203 @@
+use num_bigint::BigInt;
+use num_traits::{Euclid, Signed, Zero};
+
+use crate::math::modular::invmod;
+
+#[cfg(feature = "rand")]
+use num_bigint::Sign;
+
+#[cfg(feature = "rand")]
+use crate::data::*;
+
+// The Set 8 curve y^2 = x^3 - 95051x + 11279326 over GF(SET8_P), which has SET8_ORDER points. The base point
+// (SET8_GX, SET8_GY) generates the subgroup of prime order SET8_Q.
+pub const SET8_P: u128 = 233970423115425145524320034830162017933;
+pub const SET8_A: i64 = -95051;
+pub const SET8_B: i64 = 11279326;
+pub const SET8_ORDER: u128 = 233970423115425145498902418297807005944;
+pub const SET8_GX: u128 = 182;
+pub const SET8_GY: u128 = 85518893674295321206118380980485522083;
+pub const SET8_Q: u128 = 29246302889428143187362802287225875743;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EcPoint {
+  Infinity,
+  Affine(BigInt, BigInt)
+}
+
+// A short-Weierstrass curve y^2 = x^3 + ax + b over GF(p), along with its number of points (which the group
+// operations don't need, but attacks on the curve do). Coordinates are kept reduced into [0, p).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Curve {
+  pub p: BigInt,
+  pub a: BigInt,
+  pub b: BigInt,
+  pub order: BigInt
+}
+
+impl Curve {
+  #[allow(dead_code)]
+  pub fn set8() -> Curve {
+    Curve { p: BigInt::from(SET8_P), a: BigInt::from(SET8_A), b: BigInt::from(SET8_B), order: BigInt::from(SET8_ORDER) }
+  }
+
+  #[allow(dead_code)]
+  pub fn set8_generator() -> EcPoint { EcPoint::Affine(BigInt::from(SET8_GX), BigInt::from(SET8_GY)) }
+
+  #[allow(dead_code)]
+  pub fn contains(&self, point: &EcPoint) -> bool {
+    match point {
+      EcPoint::Infinity => true,
+      EcPoint::Affine(x, y) => (y * y - (x * x * x + &self.a * x + &self.b)).rem_euclid(&self.p).is_zero()
+    }
+  }
+
+  #[allow(dead_code)]
+  pub fn negate(&self, point: &EcPoint) -> EcPoint {
+    match point {
+      EcPoint::Infinity => EcPoint::Infinity,
+      EcPoint::Affine(x, y) => EcPoint::Affine(x.clone(), (-y).rem_euclid(&self.p))
+    }
+  }
+
+  // The chord-and-tangent group law. Note that b never appears, so points off the curve are added as though they were
+  // on the curve with the same a and some other b; invalid-curve attacks rely on exactly that.
+  #[allow(dead_code)]
+  pub fn add(&self, p1: &EcPoint, p2: &EcPoint) -> EcPoint {
+    let ((x1, y1), (x2, y2)) = match (p1, p2) {
+      (EcPoint::Infinity, _) => return p2.clone(),
+      (_, EcPoint::Infinity) => return p1.clone(),
+      (EcPoint::Affine(x1, y1), EcPoint::Affine(x2, y2)) => ((x1, y1), (x2, y2))
+    };
+
+    if *p1 == self.negate(p2) {
+      return EcPoint::Infinity;
+    }
+
+    let slope = if p1 == p2 {
+      (BigInt::from(3) * x1 * x1 + &self.a) * invmod(&(BigInt::from(2) * y1), &self.p).unwrap()
+    } else {
+      (y2 - y1) * invmod(&(x2 - x1), &self.p).unwrap()
+    };
+
+    let x3 = (&slope * &slope - x1 - x2).rem_euclid(&self.p);
+    let y3 = (slope * (x1 - &x3) - y1).rem_euclid(&self.p);
+    return EcPoint::Affine(x3, y3);
+  }
+
+  #[allow(dead_code)]
+  pub fn double(&self, point: &EcPoint) -> EcPoint { self.add(point, point) }
+
+  // k * point by double-and-add over the bits of k, most significant first. A negative k multiplies the negated point.
+  #[allow(dead_code)]
+  pub fn scalar_mul(&self, point: &EcPoint, k: &BigInt) -> EcPoint {
+    let base = if k.is_negative() { self.negate(point) } else { point.clone() };
+    let k = k.abs();
+
+    let mut res = EcPoint::Infinity;
+    for i in (0..k.bits()).rev() {
+      res = self.double(&res);
+      if k.bit(i) {
+        res = self.add(&res, &base);
+      }
+    }
+
+    return res;
+  }
+}
+
+// One side of an ECDH exchange over a curve and base point of prime order q: the same shape as finite-field DH, with
+// the public key private * base and the shared secret private * (the other party's public key).
+pub struct EcdhParty {
+  curve: Curve,
+  private: BigInt,
+  public: EcPoint
+}
+
+impl EcdhParty {
+  #[allow(dead_code)]
+  pub fn from_private(curve: &Curve, base: &EcPoint, private: BigInt) -> EcdhParty {
+    let public = curve.scalar_mul(base, &private);
+    return EcdhParty { curve: curve.clone(), private, public };
+  }
+
+  // Picks a private key from [1, q). Reducing 64 bits more than q has keeps the bias from the reduction negligible.
+  #[cfg(feature = "rand")]
+  #[allow(dead_code)]
+  pub fn new(curve: &Curve, base: &EcPoint, q: &BigInt) -> EcdhParty {
+    EcdhParty::new_with_rng(curve, base, q, &mut rand::rng())
+  }
+
+  // As new, drawing the private key from rng.
+  #[cfg(feature = "rand")]
+  #[allow(dead_code)]
+  pub fn new_with_rng(curve: &Curve, base: &EcPoint, q: &BigInt, rng: &mut impl rand::RngCore) -> EcdhParty {
+    let random = BigInt::from_bytes_be(Sign::Plus, &ASCIIData::random_with(rng, q.bits().div_ceil(8) as usize + 8));
+    let private = random % (q - 1) + 1;
+    return EcdhParty::from_private(curve, base, private);
+  }
+
+  #[allow(dead_code)]
+  pub fn public_key(&self) -> &EcPoint { &self.public }
+
+  #[allow(dead_code)]
+  pub fn shared_secret(&self, other_public: &EcPoint) -> EcPoint { self.curve.scalar_mul(other_public, &self.private) }
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_group_law() -> Result<(), String> {
+    let curve = Curve::set8();
+    let g = Curve::set8_generator();
+    if !curve.contains(&g) {
+      return Err("generator is not on the curve".to_string());
+    }
+
+    let three_g = curve.add(&curve.double(&g), &g);
+    if !curve.contains(&three_g) || three_g != curve.scalar_mul(&g, &BigInt::from(3)) {
+      return Err(format!("2G + G = {:?} disagrees with 3G", three_g));
+    }
+
+    if curve.add(&g, &curve.negate(&g)) != EcPoint::Infinity {
+      return Err("G + -G is not the point at infinity".to_string());
+    }
+
+    if curve.scalar_mul(&g, &BigInt::from(SET8_Q)) != EcPoint::Infinity {
+      return Err("the generator does not have order q".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_ecdh_shared_secret() -> Result<(), String> {
+    let curve = Curve::set8();
+    let g = Curve::set8_generator();
+    let alice = EcdhParty::from_private(&curve, &g, BigInt::parse_bytes(b"1234567890123456789012345678", 10).unwrap());
+    let bob = EcdhParty::from_private(&curve, &g, BigInt::parse_bytes(b"9876543210987654321098765432", 10).unwrap());
+
+    let (s1, s2) = (alice.shared_secret(bob.public_key()), bob.shared_secret(alice.public_key()));
+    if s1 != s2 || !curve.contains(&s1) {
+      return Err(format!("parties derived {:?} and {:?}", s1, s2));
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  #[cfg(feature = "rand")]
+  fn test_ecdh_random_parties() -> Result<(), String> {
+    let (curve, g, q) = (Curve::set8(), Curve::set8_generator(), BigInt::from(SET8_Q));
+    let alice = EcdhParty::new(&curve, &g, &q);
+    let bob = EcdhParty::new(&curve, &g, &q);
+
+    if alice.shared_secret(bob.public_key()) != bob.shared_secret(alice.public_key()) {
+      return Err("random parties derived different secrets".to_string());
+    }
+
+    return Ok(());
+  }
+}
@@ -0,0 +1,189 @@
+use crate::data::*;
+
+// Compares two byte sequences without short-circuiting on the first differing byte, so the time taken does not leak
+// how long a matching prefix is. Only a length mismatch returns early.
+#[allow(dead_code)]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+  if a.len() != b.len() {
+    return false;
+  }
+
+  let mut diff = 0u8;
+  for (x, y) in a.iter().zip(b.iter()) {
+    diff |= x ^ y;
+  }
+
+  return diff == 0;
+}
+
+// Like constant_time_eq, but a length mismatch does not return early either: both inputs are walked to the length of
+// the longer one, so the time depends only on that length and not on the contents of either input.
+#[allow(dead_code)]
+pub fn secure_compare(a: &[u8], b: &[u8]) -> bool {
+  let mut diff = (a.len() != b.len()) as u8;
+  for i in 0..a.len().max(b.len()) {
+    diff |= a.get(i).copied().unwrap_or(0) ^ b.get(i).copied().unwrap_or(0);
+  }
+
+  return diff == 0;
+}
+
+// Bytes of context shown on either side of the first difference by assert_data_eq.
+const DIFF_CONTEXT: usize = 8;
+
+// For tests: Ok if actual equals expected, otherwise an error giving the first index at which they differ (or at which
+// the shorter one ends) and up to DIFF_CONTEXT bytes either side of it in hex, with ".." marking where the windows
+// were cut off.
+#[allow(dead_code)]
+pub fn assert_data_eq(actual: &ASCIIData, expected: &ASCIIData) -> Result<(), String> {
+  let Some(index) = (0..actual.len().max(expected.len())).find(|&i| actual.get(i) != expected.get(i)) else {
+    return Ok(());
+  };
+
+  let context = |data: &ASCIIData| {
+    let (start, end) = (index.saturating_sub(DIFF_CONTEXT), (index + DIFF_CONTEXT + 1).min(data.len()));
+    let window = if start < end { data.slice(start..end).to_hex_string() } else { String::new() };
+    format!("{}{}{}", if start > 0 { ".." } else { "" }, window, if end < data.len() { ".." } else { "" })
+  };
+
+  return Err(format!(
+    "data differs at byte {} (lengths {} and {}): actual {}, expected {}",
+    index,
+    actual.len(),
+    expected.len(),
+    context(actual),
+    context(expected)
+  ));
+}
+
+#[allow(unused_imports)]
+mod tests {
+  use super::*;
+  use std::time::{Duration, Instant};
+
+  // The Challenge 31/32 attack: recovers a mac_len-byte MAC one byte at a time, taking at each position the byte value
+  // whose guesses the verifier takes longest (by median over samples timings) to reject.
+  #[allow(dead_code)]
+  fn recover_mac_by_timing(verify: impl Fn(&[u8]) -> bool, mac_len: usize, samples: usize) -> Vec<u8> {
+    let mut guess = vec![0u8; mac_len];
+    for i in 0..mac_len {
+      let timing = |guess: &[u8]| {
+        let mut times = Vec::from_iter((0..samples).map(|_| {
+          let start = Instant::now();
+          verify(guess);
+          start.elapsed()
+        }));
+
+        times.sort();
+        times[samples / 2]
+      };
+
+      guess[i] = (0..=255u8)
+        .max_by_key(|&byte| {
+          guess[i] = byte;
+          timing(&guess)
+        })
+        .unwrap();
+    }
+
+    return guess;
+  }
+
+  // A verifier that bails out at the first wrong byte and spends a while on every byte it gets through first, as the
+  // challenge's insecure_compare does.
+  #[allow(dead_code)]
+  fn insecure_compare(a: &[u8], b: &[u8]) -> bool {
+    for (x, y) in a.iter().zip(b.iter()) {
+      if x != y {
+        return false;
+      }
+
+      let start = Instant::now();
+      while start.elapsed() < Duration::from_micros(100) {}
+    }
+
+    return a.len() == b.len();
+  }
+
+  #[test]
+  fn test_constant_time_eq() -> Result<(), String> {
+    let cases: [(&[u8], &[u8], bool); 5] = [
+      (b"YELLOW SUBMARINE", b"YELLOW SUBMARINE", true),
+      (b"YELLOW SUBMARINE", b"YELLOW SUBMARINF", false),
+      (b"YELLOW SUBMARINE", b"XELLOW SUBMARINE", false),
+      (b"YELLOW", b"YELLOW SUBMARINE", false),
+      (b"", b"", true)
+    ];
+
+    for (i, (a, b, expected)) in cases.iter().enumerate() {
+      if constant_time_eq(a, b) != *expected {
+        return Err(format!("case {} should have compared {}", i, if *expected { "equal" } else { "unequal" }));
+      }
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_secure_compare() -> Result<(), String> {
+    let cases: [(&[u8], &[u8], bool); 6] = [
+      (b"YELLOW SUBMARINE", b"YELLOW SUBMARINE", true),
+      (b"YELLOW SUBMARINE", b"YELLOW SUBMARINF", false),
+      (b"YELLOW SUBMARINE", b"XELLOW SUBMARINE", false),
+      (b"YELLOW", b"YELLOW SUBMARINE", false),
+      (b"YELLOW\0", b"YELLOW", false),
+      (b"", b"", true)
+    ];
+
+    for (i, (a, b, expected)) in cases.iter().enumerate() {
+      if secure_compare(a, b) != *expected || secure_compare(b, a) != *expected {
+        return Err(format!("case {} should have compared {}", i, if *expected { "equal" } else { "unequal" }));
+      }
+    }
+
+    return Ok(());
+  }
+
+  // The same recovery routine that reads a three-byte MAC straight out of insecure_compare learns nothing about that
+  // MAC through secure_compare; recovering all three bytes by chance would take one run in 2^24. This measures
+  // wall-clock time, so it is unreliable on a loaded machine; run it with --ignored.
+  #[test]
+  #[ignore]
+  fn test_secure_compare_resists_timing_attack() -> Result<(), String> {
+    let mac = b"\x9e\x05\xc4";
+    let res = recover_mac_by_timing(|guess| insecure_compare(guess, mac), mac.len(), 7);
+    if res != mac {
+      return Err(format!("timing attack on insecure_compare recovered {:02x?}", res));
+    }
+
+    let res = recover_mac_by_timing(|guess| secure_compare(guess, mac), mac.len(), 7);
+    if res == mac {
+      return Err("timing attack recovered the MAC through secure_compare".to_string());
+    }
+
+    return Ok(());
+  }
+
+  #[test]
+  fn test_assert_data_eq() -> Result<(), String> {
+    let expected = ASCIIData::from("Cooking MC's like a pound of bacon");
+    assert_data_eq(&expected, &expected.clone())?;
+
+    let mut bytes = expected.to_bytes();
+    bytes[20] ^= 1;
+    let expected_err = "data differs at byte 20 (lengths 34 and 34): actual ..206c696b65206120716f756e64206f6620.., \
+      expected ..206c696b65206120706f756e64206f6620..";
+    match assert_data_eq(&ASCIIData::from(bytes), &expected) {
+      Err(e) if e == expected_err => {},
+      res => return Err(format!("one-byte difference gave {:?}", res))
+    }
+
+    let expected_err = "data differs at byte 3 (lengths 3 and 34): actual 436f6f, expected 436f6f6b696e67204d432773..";
+    match assert_data_eq(&expected.truncate(3), &expected) {
+      Err(e) if e == expected_err => {},
+      res => return Err(format!("truncated data gave {:?}", res))
+    }
+
+    return Ok(());
+  }
+}
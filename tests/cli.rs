@@ -0,0 +1,76 @@
+#![allow(clippy::needless_return)]
+
+use std::fs;
+use std::process::Command;
+
+fn run(args: &[&str]) -> Result<String, String> {
+  let output = Command::new(env!("CARGO_BIN_EXE_cryptopals")).args(args).output().map_err(|e| e.to_string())?;
+  if !output.status.success() {
+    return Err(format!("cryptopals {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr)));
+  }
+
+  return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+}
+
+// Challenge 4, Set 1
+#[test]
+fn test_detect_single_byte_xor() -> Result<(), String> {
+  let stdout = run(&["4", "files/c4s1.txt"])?;
+  if !stdout.contains("line: 170\n")
+    || !stdout.contains("key: 53\n")
+    || !stdout.contains("message: Now that the party is jumping\n")
+  {
+    return Err(format!("unexpected output:\n{}", stdout));
+  }
+
+  return Ok(());
+}
+
+// Challenge 8, Set 1
+#[test]
+fn test_detect_ecb() -> Result<(), String> {
+  let block = "d880619740a8a19b7840a8a31c810a3d";
+  let lines = [
+    "8a10247f90d0a05538888ad6205882196f5f6d05c21ec8dca0cb0be02c3f8b09".to_string(),
+    format!("{}08649af70dc06f4fd5d2d69c744cd283{}{}", block, block, block),
+    "e2d9e1c3a8b5b2b8f6e0c0d3b14e1b8d0c7c59e3a7bd3b2bd1eb9b0f6f1d0c3a".to_string()
+  ];
+
+  let path = format!("{}/c8.txt", env!("CARGO_TARGET_TMPDIR"));
+  fs::write(&path, lines.join("\n")).map_err(|e| e.to_string())?;
+
+  let stdout = run(&["8", &path])?;
+  if stdout != "line: 1\nrepeated blocks: 2\n" {
+    return Err(format!("unexpected output:\n{}", stdout));
+  }
+
+  return Ok(());
+}
+
+#[test]
+fn test_unknown_challenge() -> Result<(), String> {
+  if run(&["99", "files/c4s1.txt"]).is_ok() {
+    return Err("challenge 99 should have been rejected".to_string());
+  }
+
+  return Ok(());
+}
+
+#[test]
+fn test_bad_input() -> Result<(), String> {
+  let dir = env!("CARGO_TARGET_TMPDIR");
+  let cases = [("3", "bad_hex.txt", "zz\n"), ("3", "empty.txt", ""), ("4", "odd_hex.txt", "abc\n")];
+  for (challenge, name, contents) in cases {
+    let path = format!("{}/{}", dir, name);
+    fs::write(&path, contents).map_err(|e| e.to_string())?;
+
+    let output =
+      Command::new(env!("CARGO_BIN_EXE_cryptopals")).args([challenge, &path]).output().map_err(|e| e.to_string())?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.code() != Some(1) || stderr.contains("panicked") {
+      return Err(format!("challenge {} on {:?} exited with {:?}: {}", challenge, contents, output.status, stderr));
+    }
+  }
+
+  return Ok(());
+}